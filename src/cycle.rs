@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of the sequence `x0, f(x0), f(f(x0)), ...`: `tail` states lead
+/// into a loop of `length` states that repeats forever.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Cycle {
+    pub tail: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest iteration that holds the same state as iteration `n`
+    pub(crate) fn equivalent_iteration(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm. Only ever holds two states at once, so it suits
+/// large states that are cheap to step but expensive to store.
+pub(crate) fn brent<S: Clone + Eq>(initial: &S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+/// Replays the sequence just far enough to produce the state at iteration `n`
+pub(crate) fn state_at<S: Clone + Eq>(initial: S, step: impl Fn(&S) -> S, n: usize) -> S {
+    let cycle = brent(&initial, &step);
+    (0..cycle.equivalent_iteration(n)).fold(initial, |state, _| step(&state))
+}
+
+/// Every state seen up to the first repeat, alongside the cycle they form
+pub(crate) struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S> History<S> {
+    pub(crate) fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_iteration(n)]
+    }
+}
+
+/// Remembers every state in a hash map, which finds the cycle in a single pass
+/// and keeps every state around so any iteration can be looked up afterwards.
+pub(crate) fn find_with_history<S: Clone + Eq + Hash>(
+    initial: S,
+    step: impl Fn(&S) -> S,
+) -> History<S> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = vec![];
+    let mut current = initial;
+
+    loop {
+        if let Some(tail) = seen.get(&current) {
+            let cycle = Cycle {
+                tail: *tail,
+                length: states.len() - tail,
+            };
+            return History { cycle, states };
+        }
+
+        seen.insert(current.clone(), states.len());
+        let next = step(&current);
+        states.push(current);
+        current = next;
+    }
+}
+
+#[test]
+fn both_finders_agree() {
+    let step = |x: &usize| (x * x + 1) % 97;
+    let cycle = brent(&2, step);
+    let history = find_with_history(2, step);
+
+    assert_eq!(cycle, history.cycle);
+
+    let naive = (0..1000).fold(2, |x, _| step(&x));
+    assert_eq!(state_at(2, step, 1000), naive);
+    assert_eq!(*history.state_at(1000), naive);
+}
+
+#[test]
+fn pure_cycle_has_no_tail() {
+    let step = |x: &u8| (x + 1) % 12;
+    assert_eq!(
+        brent(&0, step),
+        Cycle {
+            tail: 0,
+            length: 12
+        }
+    );
+    assert_eq!(state_at(0, step, 1_000_000_000), (1_000_000_000 % 12) as u8);
+}
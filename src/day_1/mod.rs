@@ -1,3 +1,8 @@
+use std::io::BufRead;
+
+use crate::parse::Line;
+use crate::stream::try_for_each_line;
+
 static INPUT_1: &'static str = include_str!("./input_1.txt");
 
 ///
@@ -22,36 +27,62 @@ static INPUT_1: &'static str = include_str!("./input_1.txt");
 ///
 /// Consider your entire calibration document. What is the sum of all of the calibration values?
 /// takes an input str and determines the "calibration value"
-fn solve_1(input: &str) -> usize {
+fn solve_1(input: &str) -> eyre::Result<usize> {
+    solve_1_from(input.as_bytes())
+}
+
+/// [`solve_1`] over any reader, one line at a time
+fn solve_1_from(reader: impl BufRead) -> eyre::Result<usize> {
     let mut o = 0usize;
-    for line in input.lines() {
-        let mut iter = line
-            .trim()
-            .chars()
-            .filter_map(|c| c.is_digit(10).then(|| c.to_digit(10)).flatten())
-            .map(|c| c as usize);
-        match (iter.next(), iter.last()) {
-            (Some(first), Some(last)) => {
-                o += first * 10;
-                o += last
-            }
-            (Some(first), _) => {
-                o += first * 10;
-                o += first
-            }
-            _ => {}
-        }
-    }
+    try_for_each_line(reader, |_, line| {
+        o += calibration_value(line);
+        Ok(())
+    })?;
+
+    return Ok(o);
+}
 
-    return o;
+/// The first and last digit of a line, nothing when the line has no digits
+fn calibration_value(line: &str) -> usize {
+    let mut iter = line
+        .trim()
+        .chars()
+        .filter_map(|c| c.is_digit(10).then(|| c.to_digit(10)).flatten())
+        .map(|c| c as usize);
+    match (iter.next(), iter.last()) {
+        (Some(first), Some(last)) => first * 10 + last,
+        (Some(first), _) => first * 10 + first,
+        _ => 0,
+    }
 }
 
 /// Your calculation isn't quite right. It looks like some of the digits are actually spelled out with letters:
 /// one, two, three, four, five, six, seven, eight, and nine also count as valid "digits".
 //
 // Equipped with this new information, you now need to find the real first and last digit on each line. For example:
-fn solve_2(input: &str) -> usize {
+fn solve_2(input: &str) -> eyre::Result<usize> {
+    solve_2_from(input.as_bytes())
+}
+
+/// [`solve_2`] over any reader, one line at a time
+fn solve_2_from(reader: impl BufRead) -> eyre::Result<usize> {
     let mut output = 0usize;
+    try_for_each_line(reader, |number, text| {
+        let Some(value) = spelled_calibration_value(text) else {
+            let line = Line::new(number, text);
+            return Err(line.error("Could not find two digits").wrap_err(format!(
+                "Day 1 part 2: invalid calibration on line {number}"
+            )));
+        };
+        output += value;
+        Ok(())
+    })?;
+
+    return Ok(output);
+}
+
+/// The first and last digit of a line, spelled out or not
+fn spelled_calibration_value(line: &str) -> Option<usize> {
     static DIGIT_SPELLINGS: [&'static str; 10] = [
         "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
     ];
@@ -72,48 +103,37 @@ fn solve_2(input: &str) -> usize {
             })
     }
 
-    // accumulate forward and backward, in two separate iterators
-    // each iterator is an iterator of lines containing an iterator of chars, one going forwards the other going backwards
-    let forwards = input.lines().map(|l| l.trim()).map(|l| l.chars());
-    let backwards = input.lines().map(|l| l.trim()).map(|l| l.chars().rev());
-    let zipper = forwards.zip(backwards);
-
-    // zip both iterators together, this represents a single line with one iterator going forward and another going backward\
-    // We know both iterators are the same length because they both originate from `input.lines()`
-    for (forwards, reverse) in zipper {
-        let mut digits = (None, None);
-        let mut forward = String::new();
-        let mut backward = String::new();
-        // realistically there's an optimization here where the zip can stop if the pointers touch
-        // but i can't be bothered
-        for (char_f, char_b) in forwards.zip(reverse) {
-            if digits.0.is_some() && digits.1.is_some() {
-                break;
-            }
-            digits = (
-                digits.0.or({
-                    forward.push(char_f);
-
-                    check(&forward)
-                }),
-                digits.1.or({
-                    backward.insert(0, char_b);
-
-                    check(&backward)
-                }),
-            );
+    // walk the line from both ends at once, one iterator going forward and another going backward
+    let line = line.trim();
+    let mut digits = (None, None);
+    let mut forward = String::new();
+    let mut backward = String::new();
+    // realistically there's an optimization here where the zip can stop if the pointers touch
+    // but i can't be bothered
+    for (char_f, char_b) in line.chars().zip(line.chars().rev()) {
+        if digits.0.is_some() && digits.1.is_some() {
+            break;
         }
+        digits = (
+            digits.0.or({
+                forward.push(char_f);
 
-        if let (Some(tens), Some(ones)) = digits {
-            output += tens * 10;
-            output += ones;
-        } else {
-            panic!("Could not find two digits")
-        }
+                check(&forward)
+            }),
+            digits.1.or({
+                backward.insert(0, char_b);
+
+                check(&backward)
+            }),
+        );
     }
 
-    return output;
+    match digits {
+        (Some(tens), Some(ones)) => Some(tens * 10 + ones),
+        _ => None,
+    }
 }
+
 #[test]
 fn sample_case() {
     let output = solve_1(
@@ -123,21 +143,52 @@ fn sample_case() {
           a1b2c3d4e5f
           treb7uchet
           ",
-    );
+    )
+    .unwrap();
 
     assert_eq!(output, 142)
 }
 
 #[test]
 fn full_case_1() {
-    let output = solve_1(&INPUT_1);
+    let output = solve_1(&INPUT_1).unwrap();
 
     assert_eq!(output, 54081);
 }
 
 #[test]
 fn full_case_2() {
-    let output = solve_2(&INPUT_1);
+    let output = solve_2(&INPUT_1).unwrap();
 
     assert_eq!(output, 54649);
 }
+
+#[test]
+fn missing_digits_are_an_error() {
+    let error = solve_2("two1nine\nabc\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Day 1 part 2: invalid calibration on line 2"
+    );
+    assert_eq!(
+        error.root_cause().to_string(),
+        "line 2, column 1: Could not find two digits\n  |\n2 | abc\n  | ^^^"
+    );
+}
+
+#[test]
+fn streams_large_inputs() {
+    use crate::stream::Repeated;
+
+    assert_eq!(solve_1_from(INPUT_1.as_bytes()).unwrap(), 54081);
+
+    let lines = 100_000;
+    assert_eq!(
+        solve_1_from(Repeated::new("pqr3stu8vwx\n", lines)).unwrap(),
+        38 * lines
+    );
+    assert_eq!(
+        solve_2_from(Repeated::new("xtwone3four\r\n", lines)).unwrap(),
+        24 * lines
+    );
+}
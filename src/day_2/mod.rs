@@ -1,8 +1,12 @@
-use eyre::{bail, eyre, Context};
+use eyre::Context;
 use std::collections::HashMap;
+use std::io::BufRead;
 
 use std::str::FromStr;
 
+use crate::parse::Line;
+use crate::stream::try_for_each_line;
+
 /// You're launched high into the atmosphere! The apex of your trajectory just
 /// barely reaches the surface of a large island floating in the sky. You gently
 /// land in a fluffy pile of leaves. It's quite cold, but you don't see much snow.
@@ -50,16 +54,6 @@ use std::str::FromStr;
 /// Determine which games would have been possible if the bag had been loaded with only
 /// 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs of those games?
 
-macro_rules! unwrap_continue {
-    ($ex:expr) => {{
-        if let Some(v) = $ex {
-            v
-        } else {
-            continue;
-        }
-    }};
-}
-
 #[derive(Debug)]
 struct Game {
     id: usize,
@@ -69,32 +63,36 @@ struct Game {
 #[derive(Debug)]
 struct Attempt(HashMap<&'static str, usize>);
 
-impl FromStr for Attempt {
-    type Err = eyre::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Attempt {
+    /// A comma separated handful like `3 blue, 4 red`
+    fn parse(line: Line) -> eyre::Result<Self> {
         let mut this = Self(Default::default());
-        let items = s.split(",");
-        'color_loop: for color in ["red", "green", "blue"] {
-            for item in items.clone() {
-                let item = item.trim();
+        for item in line.groups(',') {
+            let mut words = item.words();
+            let (Some(count), Some(color), None) = (words.next(), words.next(), words.next())
+            else {
+                return Err(item.error(format!("Expected `<count> <color>`, got {}", item.text)));
+            };
+            let color = ["red", "green", "blue"]
+                .into_iter()
+                .find(|c| *c == color.text)
+                .ok_or_else(|| color.error(format!("Unknown color {}", color.text)))?;
 
-                let candidate = unwrap_continue!(item.strip_suffix(color));
-                let digit_character = unwrap_continue!(candidate.trim().parse::<usize>().ok());
-
-                this.0.insert(color, digit_character);
-                continue 'color_loop;
-            }
-        }
-
-        if this.0.len() == 0 {
-            bail!("Could not parse out attempt from {s} got {:?}", &this.0);
+            this.0.insert(color, count.number()?);
         }
 
         return Ok(this);
     }
 }
 
+impl FromStr for Attempt {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(Line::new(1, s))
+    }
+}
+
 #[test]
 fn parse_attempt() {
     let attempt = Attempt::from_str("3 red, 5 blue, 6 green");
@@ -105,71 +103,108 @@ fn parse_attempt() {
     assert_eq!(map["green"], 6);
 }
 
+impl Game {
+    /// A `Game N: ...` line with its attempts separated by `;`
+    fn parse(line: Line) -> eyre::Result<Self> {
+        let (id, game) = line.labeled("Game")?;
+        let attempts = game
+            .groups(';')
+            .map(Attempt::parse)
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self { id, attempts })
+    }
+}
+
 impl FromStr for Game {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (id, game) = s
-            .split_once(':')
-            .ok_or(eyre!("Incorrect format of {s}. Expects single :"))?;
+        Self::parse(Line::new(1, s))
+    }
+}
 
-        let attempts = game
-            .split(';')
-            .map(Attempt::from_str)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let game_id = id
-            .strip_prefix("Game")
-            .ok_or(eyre!("Incorrect game prefix {id}"))?
-            .trim()
-            .parse()
-            .context("Not a valid game id")?;
-
-        Ok(Self {
-            id: game_id,
-            attempts,
-        })
+/// Whether the game fits a bag of 12 red, 13 green and 14 blue cubes
+fn is_possible(game: &Game) -> bool {
+    for attempt in &game.attempts {
+        for (color, roll) in attempt.0.iter() {
+            match *color {
+                "red" if *roll > 12 => return false,
+                "green" if *roll > 13 => return false,
+                "blue" if *roll > 14 => return false,
+                _ => continue,
+            }
+        }
     }
+    true
+}
+
+/// The product of the fewest cubes of each color the game could be played with
+fn power(game: &Game) -> usize {
+    let max_values = game.attempts.iter().fold(
+        (0usize, 0usize, 0usize),
+        |(mut red, mut green, mut blue), g| {
+            red = red.max(g.0.get("red").copied().unwrap_or_default());
+            green = green.max(g.0.get("green").copied().unwrap_or_default());
+            blue = blue.max(g.0.get("blue").copied().unwrap_or_default());
+
+            (red, green, blue)
+        },
+    );
+
+    return max_values.0 * max_values.1 * max_values.2;
 }
 
 fn compute_valid_games(games: Vec<Game>) -> usize {
     games
         .iter()
-        .filter_map(|g| {
-            for attempt in &g.attempts {
-                for (color, roll) in attempt.0.iter() {
-                    match *color {
-                        "red" if *roll > 12 => return None,
-                        "green" if *roll > 13 => return None,
-                        "blue" if *roll > 14 => return None,
-                        _ => continue,
-                    }
-                }
-            }
-
-            Some(g.id)
-        })
+        .filter_map(|g| is_possible(g).then_some(g.id))
         .sum()
 }
 
 fn compute_powers(games: Vec<Game>) -> usize {
-    games
-        .iter()
-        .map(|g| {
-            let max_values = g.attempts.iter().fold(
-                (0usize, 0usize, 0usize),
-                |(mut red, mut green, mut blue), g| {
-                    red = red.max(g.0.get("red").copied().unwrap_or_default());
-                    green = green.max(g.0.get("green").copied().unwrap_or_default());
-                    blue = blue.max(g.0.get("blue").copied().unwrap_or_default());
-
-                    (red, green, blue)
-                },
-            );
-
-            return max_values.0 * max_values.1 * max_values.2;
-        })
-        .sum()
+    games.iter().map(power).sum()
+}
+
+/// Parses one game per line and folds each into `score` as soon as it's
+/// read, so only the current line is ever held. Blank lines are skipped.
+fn sum_games(
+    reader: impl BufRead,
+    part: usize,
+    score: impl Fn(&Game) -> usize,
+) -> eyre::Result<usize> {
+    let mut sum = 0;
+    try_for_each_line(reader, |number, text| {
+        let line = Line::new(number, text);
+        if line.is_blank() {
+            return Ok(());
+        }
+        let game = Game::parse(line)
+            .with_context(|| format!("Day 2 part {part}: invalid game on line {number}"))?;
+        sum += score(&game);
+        Ok(())
+    })?;
+    Ok(sum)
+}
+
+fn solve_1(input: &str) -> eyre::Result<usize> {
+    solve_1_from(input.as_bytes())
+}
+
+fn solve_1_from(reader: impl BufRead) -> eyre::Result<usize> {
+    sum_games(
+        reader,
+        1,
+        |game| if is_possible(game) { game.id } else { 0 },
+    )
+}
+
+fn solve_2(input: &str) -> eyre::Result<usize> {
+    solve_2_from(input.as_bytes())
+}
+
+fn solve_2_from(reader: impl BufRead) -> eyre::Result<usize> {
+    sum_games(reader, 2, power)
 }
 
 #[test]
@@ -192,24 +227,41 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 
 #[test]
 fn first_full_case() {
-    let game = include_str!("./input_1.txt")
-        .trim()
-        .lines()
-        .map(Game::from_str)
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap();
-
-    assert_eq!(compute_valid_games(game), 2632)
+    assert_eq!(solve_1(include_str!("./input_1.txt")).unwrap(), 2632)
 }
 
 #[test]
 fn second_full_test_case() {
-    let game = include_str!("./input_1.txt")
-        .trim()
-        .lines()
-        .map(Game::from_str)
-        .collect::<Result<Vec<_>, _>>()
+    assert_eq!(solve_2(include_str!("./input_1.txt")).unwrap(), 69629)
+}
+
+#[test]
+fn errors_name_the_line() {
+    let error = solve_1("Game 1: 3 blue\nGame two: 4 red").unwrap_err();
+    assert_eq!(error.to_string(), "Day 2 part 1: invalid game on line 2");
+    assert_eq!(
+        error.root_cause().to_string(),
+        "line 2, column 6: Not a valid number two: invalid digit found in string\n  |\n2 | Game two: 4 red\n  |      ^^^"
+    );
+
+    let error = solve_2("Game 1: 3 blue, 4 red\nGame 2: 3 blue; 2 purple").unwrap_err();
+    let diagnostic = error
+        .root_cause()
+        .downcast_ref::<crate::diagnostic::Diagnostic>()
         .unwrap();
+    assert_eq!(diagnostic.message, "Unknown color purple");
+    assert_eq!((diagnostic.line, diagnostic.column()), (2, 19));
+}
+
+#[test]
+fn streams_large_inputs() {
+    use crate::stream::Repeated;
 
-    assert_eq!(compute_powers(game), 69629)
+    let games = 20_000;
+    let line = "Game 7: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n";
+    assert_eq!(solve_1_from(Repeated::new(line, games)).unwrap(), 7 * games);
+    assert_eq!(
+        solve_2_from(Repeated::new(line, games)).unwrap(),
+        48 * games
+    );
 }
@@ -0,0 +1,286 @@
+use std::ops::{BitAnd, BitOr};
+
+use super::model::{Cell, Coordinate, Grid, Vector};
+
+/// A boolean grid with every row packed into `u64` words, bit `x % 64` of
+/// word `x / 64` holding column `x`. Whole-grid updates run a word at a time,
+/// which is what cellular simulations spend most of their time on.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(super) struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitGrid {
+    pub(super) fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            words_per_row,
+            bits: vec![0; words_per_row * height],
+        }
+    }
+
+    pub(super) fn from_grid(grid: &Grid, predicate: impl Fn(Cell) -> bool) -> Self {
+        let mut this = Self::new(grid.width, grid.height);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if grid.get(Coordinate(x, y)).is_some_and(&predicate) {
+                    this.set(Coordinate(x, y), true);
+                }
+            }
+        }
+        this
+    }
+
+    /// Turns set bits into `on` and clear bits into `off`
+    pub(super) fn to_grid(&self, on: Cell, off: Cell) -> Grid {
+        let cells = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinate(x, y)))
+            .map(|c| if self.get(c) { on } else { off })
+            .collect();
+        Grid::from_cells(self.width, cells)
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.bits[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub(super) fn get(&self, index: Coordinate) -> bool {
+        if index.0 >= self.width || index.1 >= self.height {
+            return false;
+        }
+        (self.row(index.1)[index.0 / 64] >> (index.0 % 64)) & 1 == 1
+    }
+
+    pub(super) fn set(&mut self, index: Coordinate, value: bool) {
+        assert!(index.0 < self.width && index.1 < self.height);
+        let word = &mut self.bits[index.1 * self.words_per_row + index.0 / 64];
+        let mask = 1 << (index.0 % 64);
+        if value {
+            *word |= mask
+        } else {
+            *word &= !mask
+        }
+    }
+
+    pub(super) fn count_ones(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Moves every set cell by `by`. Cells pushed past the edge are dropped
+    /// and the vacated cells are cleared.
+    pub(super) fn shift(&self, by: Vector) -> Self {
+        let mut output = Self::new(self.width, self.height);
+        let Vector(dx, dy) = by;
+        if dx.unsigned_abs() >= self.width || dy.unsigned_abs() >= self.height {
+            return output;
+        }
+
+        for y in 0..self.height {
+            let Some(source_y) = y.checked_add_signed(-dy).filter(|y| *y < self.height) else {
+                continue;
+            };
+            let destination =
+                &mut output.bits[y * self.words_per_row..(y + 1) * self.words_per_row];
+            shift_row(self.row(source_y), destination, dx);
+        }
+
+        output.clear_padding();
+        output
+    }
+
+    /// Keeps the unused high bits of each row's last word at zero so
+    /// `count_ones` and equality only see real cells
+    fn clear_padding(&mut self) {
+        let used = self.width % 64;
+        if used == 0 {
+            return;
+        }
+        let mask = (1u64 << used) - 1;
+        for row in self.bits.chunks_mut(self.words_per_row) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+}
+
+/// Shifts a packed row towards higher columns for positive `dx`
+fn shift_row(source: &[u64], destination: &mut [u64], dx: isize) {
+    let (words, bits) = (dx.unsigned_abs() / 64, dx.unsigned_abs() % 64);
+    let word = |index: Option<usize>| index.and_then(|i| source.get(i)).copied().unwrap_or(0);
+
+    for (index, slot) in destination.iter_mut().enumerate() {
+        *slot = if dx >= 0 {
+            let near = word(index.checked_sub(words));
+            let far = word(index.checked_sub(words + 1));
+            if bits == 0 {
+                near
+            } else {
+                (near << bits) | (far >> (64 - bits))
+            }
+        } else {
+            let near = word(Some(index + words));
+            let far = word(Some(index + words + 1));
+            if bits == 0 {
+                near
+            } else {
+                (near >> bits) | (far << (64 - bits))
+            }
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        assert_eq!((self.width, self.height), (rhs.width, rhs.height));
+        let mut output = self.clone();
+        output
+            .bits
+            .iter_mut()
+            .zip(&rhs.bits)
+            .for_each(|(a, b)| *a &= b);
+        output
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        assert_eq!((self.width, self.height), (rhs.width, rhs.height));
+        let mut output = self.clone();
+        output
+            .bits
+            .iter_mut()
+            .zip(&rhs.bits)
+            .for_each(|(a, b)| *a |= b);
+        output
+    }
+}
+
+/// One step of "where can I be after n moves": every reachable cell spreads
+/// to its orthogonal neighbors, limited to the open cells
+#[cfg(test)]
+fn spread(reachable: &BitGrid, open: &BitGrid) -> BitGrid {
+    let spread = &(&reachable.shift(Vector(1, 0)) | &reachable.shift(Vector(-1, 0)))
+        | &(&reachable.shift(Vector(0, 1)) | &reachable.shift(Vector(0, -1)));
+    &spread & open
+}
+
+#[test]
+fn shifts_across_word_boundaries() {
+    let mut grid = BitGrid::new(130, 3);
+    grid.set(Coordinate(63, 1), true);
+    grid.set(Coordinate(129, 0), true);
+
+    let right = grid.shift(Vector(1, 0));
+    assert!(right.get(Coordinate(64, 1)));
+    // the bit at the far edge falls off instead of wrapping into padding
+    assert_eq!(right.count_ones(), 1);
+
+    let moved = grid.shift(Vector(-65, 1));
+    assert!(moved.get(Coordinate(64, 1)));
+    assert_eq!(moved.count_ones(), 1);
+
+    assert_eq!(grid.shift(Vector(0, 3)).count_ones(), 0);
+    let diagonal = grid.shift(Vector(-67, 1));
+    assert!(diagonal.get(Coordinate(62, 1)));
+    assert_eq!(diagonal.count_ones(), 1);
+}
+
+#[test]
+fn grid_round_trip() {
+    let grid: Grid = r"
+    467..114..
+    ...*......
+    ..35..633.
+    "
+    .parse()
+    .unwrap();
+
+    let digits = BitGrid::from_grid(&grid, |c| matches!(c, Cell::Digit(_)));
+    let symbols = BitGrid::from_grid(&grid, |c| matches!(c, Cell::Gear));
+    assert_eq!(digits.count_ones(), 11);
+    assert_eq!((&digits | &symbols).count_ones(), 12);
+    assert_eq!((&digits & &symbols).count_ones(), 0);
+
+    assert_eq!(
+        digits.to_grid(Cell::Symbol('#'), Cell::Empty).to_string(),
+        "###..###..\n..........\n..##..###."
+    );
+}
+
+#[test]
+fn reachability_settles_into_a_cycle() {
+    let grid: Grid = include_str!("./input.txt").parse().unwrap();
+    let open = BitGrid::from_grid(&grid, |c| matches!(c, Cell::Empty));
+    let mut start = BitGrid::new(grid.width, grid.height);
+    start.set(Coordinate(0, 0), true);
+
+    // once the region is filled the reachable cells flip between the two
+    // checkerboard colors every step
+    let step = |reachable: &BitGrid| spread(reachable, &open);
+    let history = crate::cycle::find_with_history(start.clone(), step);
+    assert_eq!(history.cycle.length, 2);
+    assert_eq!(crate::cycle::brent(&start, step), history.cycle);
+
+    let far = crate::cycle::state_at(start, step, 1_000_000_000);
+    assert_eq!(&far, history.state_at(1_000_000_000));
+}
+
+/// Not a real benchmark harness, just a timing comparison of the two
+/// representations running the same reachability simulation.
+/// Run with `cargo test --release bitgrid -- --ignored --nocapture`
+#[ignore]
+#[test]
+fn benchmark_against_cells() {
+    use std::time::Instant;
+
+    let grid: Grid = include_str!("./input.txt").parse().unwrap();
+    let steps = 200;
+    let start = Coordinate(0, 0);
+
+    let timer = Instant::now();
+    let open = BitGrid::from_grid(&grid, |c| matches!(c, Cell::Empty));
+    let mut reachable = BitGrid::new(grid.width, grid.height);
+    reachable.set(start, true);
+    for _ in 0..steps {
+        reachable = spread(&reachable, &open);
+    }
+    let packed = (reachable.count_ones(), timer.elapsed());
+
+    // the same spread over one bool per cell, double buffered
+    let timer = Instant::now();
+    let (width, height) = (grid.width, grid.height);
+    let index = |c: Coordinate| c.1 * width + c.0;
+    let mut current = vec![false; width * height];
+    let mut next = current.clone();
+    current[index(start)] = true;
+    for _ in 0..steps {
+        for y in 0..height {
+            for x in 0..width {
+                let c = Coordinate(x, y);
+                next[index(c)] = matches!(grid.get(c), Some(Cell::Empty))
+                    && [Vector(1, 0), Vector(-1, 0), Vector(0, 1), Vector(0, -1)]
+                        .into_iter()
+                        .filter_map(|v| c + v)
+                        .any(|n| n.0 < width && n.1 < height && current[index(n)]);
+            }
+        }
+        std::mem::swap(&mut current, &mut next);
+    }
+    let cells = (current.iter().filter(|r| **r).count(), timer.elapsed());
+
+    println!(
+        "BitGrid: {:?}, Vec<bool> over the grid: {:?}",
+        packed.1, cells.1
+    );
+    assert_eq!(packed.0, cells.0);
+}
@@ -0,0 +1,274 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Context};
+
+use super::model::{Cell, Coordinate, Grid};
+
+pub(super) type Rgb = [u8; 3];
+
+/// A plain RGB raster, row-major from the top left
+pub(super) struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Grid {
+    /// Paints every cell as a `scale` x `scale` block of the color picked for it
+    pub(super) fn to_image(&self, scale: usize, color: impl Fn(Coordinate, Cell) -> Rgb) -> Image {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let coordinate = Coordinate(x / scale, y / scale);
+                let pixel = self
+                    .get(coordinate)
+                    .map(|cell| color(coordinate, cell))
+                    .unwrap_or_default();
+                pixels.push(pixel);
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+impl Image {
+    /// Binary PPM (P6), which nearly every image viewer opens
+    pub(super) fn write_ppm(&self, mut out: impl Write) -> std::io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            out.write_all(pixel)?;
+        }
+        Ok(())
+    }
+
+    /// 8-bit RGB PNG. The image data is deflated with stored blocks only,
+    /// which keeps the encoder tiny at the cost of file size.
+    pub(super) fn write_png(&self, mut out: impl Write) -> std::io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, color type 2 (rgb), default compression, filter and interlace
+        header.extend([8, 2, 0, 0, 0]);
+        write_chunk(&mut out, b"IHDR", &header)?;
+
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // every scanline starts with its filter type, 0 being none
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut out, b"IEND", &[])
+    }
+
+    /// Writes the image to `path`, picking the format from the extension
+    pub(super) fn save(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+        let png = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => false,
+            Some("png") => true,
+            _ => bail!("Unsupported image format for {path:?}, expected .ppm or .png"),
+        };
+        let file = File::create(path).with_context(|| format!("Could not create {path:?}"))?;
+        let mut out = BufWriter::new(file);
+
+        if png {
+            self.write_png(&mut out)
+        } else {
+            self.write_ppm(&mut out)
+        }
+        // dropping the writer would swallow any error from the final write
+        .and_then(|()| out.flush())
+        .with_context(|| format!("Could not write image to {path:?}"))
+    }
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> std::io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none() as u8;
+        let length = block.len() as u16;
+        out.push(last);
+        out.extend(length.to_le_bytes());
+        out.extend((!length).to_le_bytes());
+        out.extend(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend(((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// Writes numbered images into a directory, one per simulation step:
+/// `frame_0000.png`, `frame_0001.png`, ...
+pub(super) struct FrameWriter {
+    directory: PathBuf,
+    prefix: String,
+    extension: &'static str,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub(super) fn new(
+        directory: impl Into<PathBuf>,
+        prefix: &str,
+        extension: &'static str,
+    ) -> eyre::Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)
+            .with_context(|| format!("Could not create frame directory {directory:?}"))?;
+
+        Ok(Self {
+            directory,
+            prefix: prefix.to_string(),
+            extension,
+            next: 0,
+        })
+    }
+
+    pub(super) fn push(&mut self, image: &Image) -> eyre::Result<PathBuf> {
+        let path = self.directory.join(format!(
+            "{}_{:04}.{}",
+            self.prefix, self.next, self.extension
+        ));
+        image.save(&path)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[test]
+fn ppm_export() {
+    let grid: Grid = r"
+    1.
+    .*
+    "
+    .parse()
+    .unwrap();
+
+    let image = grid.to_image(1, |_, cell| match cell {
+        Cell::Digit(_) => [0, 255, 0],
+        Cell::Gear => [255, 255, 0],
+        _ => [0, 0, 0],
+    });
+
+    let mut out = vec![];
+    image.write_ppm(&mut out).unwrap();
+    let mut expected = b"P6\n2 2\n255\n".to_vec();
+    expected.extend([0, 255, 0, 0, 0, 0, 0, 0, 0, 255, 255, 0]);
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn png_export() {
+    // the crc of the 4 byte chunk type "IEND" with no data is a fixed value
+    assert_eq!(crc32(b"IEND"), 0xae42_6082);
+
+    let grid: Grid = "12\n3*".parse().unwrap();
+    let image = grid.to_image(3, |_, _| [10, 20, 30]);
+    assert_eq!((image.width, image.height), (6, 6));
+
+    let mut out = vec![];
+    image.write_png(&mut out).unwrap();
+    assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&out[12..16], b"IHDR");
+    assert_eq!(&out[out.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+    // the whole IDAT chunk of a 2x2 image, checked against python's
+    // `zlib.compress(raw, 0)` which decompresses back to the scanlines
+    let image = Image {
+        width: 2,
+        height: 2,
+        pixels: vec![[10, 20, 30], [40, 50, 60], [70, 80, 90], [100, 110, 120]],
+    };
+    let mut out = vec![];
+    image.write_png(&mut out).unwrap();
+    let mut idat = vec![0, 0, 0, 25];
+    idat.extend(b"IDAT");
+    // zlib header, then one final stored block of 14 bytes
+    idat.extend([0x78, 0x01, 0x01, 0x0e, 0x00, 0xf1, 0xff]);
+    idat.extend([0, 10, 20, 30, 40, 50, 60, 0, 70, 80, 90, 100, 110, 120]);
+    // adler-32 of the scanlines, then the chunk crc
+    idat.extend([0x0f, 0x18, 0x03, 0x0d, 0x02, 0x25, 0xc5, 0x3f]);
+    // the signature and IHDR chunk take up the first 33 bytes
+    assert_eq!(&out[33..33 + idat.len()], idat.as_slice());
+}
+
+/// A directory of its own for every test, and for every run of the tests
+#[cfg(test)]
+fn scratch_directory(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("aoc-day-3-{name}-{}", std::process::id()))
+}
+
+#[test]
+fn numbered_frames() {
+    let directory = scratch_directory("frames");
+    let mut frames = FrameWriter::new(&directory, "frame", "ppm").unwrap();
+    let grid: Grid = "1.\n.*".parse().unwrap();
+
+    for step in 0..3u8 {
+        let path = frames.push(&grid.to_image(1, |_, _| [step; 3])).unwrap();
+        assert_eq!(path, directory.join(format!("frame_{step:04}.ppm")));
+        assert!(path.exists());
+    }
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn unsupported_formats_write_nothing() {
+    let directory = scratch_directory("formats");
+    std::fs::create_dir_all(&directory).unwrap();
+    let image = "1.".parse::<Grid>().unwrap().to_image(1, |_, _| [0; 3]);
+
+    let path = directory.join("frame.gif");
+    let error = image.save(&path).unwrap_err();
+    assert!(
+        error.to_string().starts_with("Unsupported image format"),
+        "{error}"
+    );
+    assert!(!path.exists());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
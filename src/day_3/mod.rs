@@ -1,11 +1,18 @@
+use eyre::{bail, eyre};
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Write};
 use std::ops::Add;
 use std::str::FromStr;
 
 use model::*;
+use region::*;
+use render::*;
 
+mod bitgrid;
+mod image;
 mod model;
+mod region;
+mod render;
 
 #[test]
 fn grid_parsing() {
@@ -39,56 +46,76 @@ fn grid_parsing() {
     )
 }
 
+fn is_part_number(grid: &Grid, component: &Component) -> bool {
+    component.cells.iter().any(|c| {
+        c.get_neighbors()
+            .into_iter()
+            .flatten()
+            .any(|c| matches!(grid.get(c), Some(Cell::Symbol(_))))
+    })
+}
+
 fn get_model_numbers(grid: Grid) -> eyre::Result<Vec<usize>> {
-    let mut esults = vec![];
-    let iterator = (0..grid.height)
+    // every number is a run of digits along a row, so the model numbers are
+    // just the row-connected digit components that touch a symbol
+    let components = grid.label_components(Adjacency::Row, |c| matches!(c, Cell::Digit(_)));
+
+    let esults = components
         .into_iter()
-        .map(|y| ((0..grid.width).into_iter(), y));
-
-    for (row, y) in iterator {
-        let mut current_digits = vec![];
-        let mut should_flush = false;
-
-        // accumulate a number in current number, and see if it should flush
-        macro_rules! flush {
-            () => {{
-                if should_flush && current_digits.len() > 0 {
-                    esults.push(
-                        current_digits
-                            .iter()
-                            .rev()
-                            .enumerate()
-                            .fold(0, |acc, (idx, v)| acc + v * 10usize.pow(idx as u32)),
-                    );
-                }
-                current_digits.clear();
-                should_flush = false;
-            };};
-        }
+        .filter(|component| is_part_number(&grid, component))
+        .map(|component| {
+            component
+                .cells
+                .iter()
+                .filter_map(|c| match grid.get(*c) {
+                    Some(Cell::Digit(v)) => Some(v),
+                    _ => None,
+                })
+                .fold(0, |acc, v| acc * 10 + v)
+        })
+        .collect();
 
-        for x in row {
-            let cell = grid.get(Coordinate(x, y));
-            match cell {
-                None => flush!(),
-                Some(Cell::Empty | Cell::Symbol(_) | Cell::Gear) => {
-                    flush!();
-                }
-                Some(Cell::Digit(v)) => {
-                    current_digits.push(v);
-                    should_flush = should_flush
-                        || Coordinate(x, y)
-                            .get_neighbors()
-                            .into_iter()
-                            .filter_map(|x| x)
-                            .any(|c| matches!(grid.get(c), Some(Cell::Symbol(_))));
-                }
-            }
+    Ok(esults)
+}
+
+/// Styles for looking at a solved grid: counted part numbers in green,
+/// numbers that were skipped in red and gears in yellow
+fn part_number_styles(grid: &Grid) -> impl Fn(Coordinate, Cell) -> Style {
+    let mut counted = HashSet::new();
+    let mut uncounted = HashSet::new();
+    for component in grid.label_components(Adjacency::Row, |c| matches!(c, Cell::Digit(_))) {
+        if is_part_number(grid, &component) {
+            counted.extend(component.cells);
+        } else {
+            uncounted.extend(component.cells);
         }
+    }
 
-        flush!();
+    move |coordinate, cell| match cell {
+        Cell::Gear => Style::Yellow,
+        Cell::Digit(_) if counted.contains(&coordinate) => Style::Green,
+        Cell::Digit(_) if uncounted.contains(&coordinate) => Style::Red,
+        _ => Style::Plain,
     }
+}
 
-    Ok(esults)
+#[test]
+fn render_part_numbers() {
+    let grid: Grid = r"
+    467..114..
+    ...*...#..
+    "
+    .parse()
+    .unwrap();
+
+    let rendered = grid.render(part_number_styles(&grid)).to_string();
+
+    // gears don't make a part number, so only 114 is counted here
+    let (g, r, y, x) = ("\x1b[32m", "\x1b[31m", "\x1b[33m", "\x1b[0m");
+    assert_eq!(
+        rendered,
+        format!("{r}4{x}{r}6{x}{r}7{x}..{g}1{x}{g}1{x}{g}4{x}..\n...{y}*{x}...#..")
+    );
 }
 
 fn get_gears(grid: Grid) -> eyre::Result<Vec<usize>> {
@@ -163,17 +190,25 @@ fn get_gears(grid: Grid) -> eyre::Result<Vec<usize>> {
                             .product(),
                     );
                 } else {
-                    assert_eq!(unique_y_coordinates.len(), 2);
-                    let mut iterator = unique_y_coordinates.iter();
-                    let (y1, y2) = (*iterator.next().unwrap(), *iterator.next().unwrap());
-                    let first = vectors.iter().find(|v| v.1 == y1).unwrap();
-
-                    let second = vectors.iter().find(|v| v.1 == y2).unwrap();
+                    let mut rows = unique_y_coordinates.iter().copied();
+                    let (Some(y1), Some(y2), None) = (rows.next(), rows.next(), rows.next()) else {
+                        bail!(
+                            "Day 3 part 2: gear at {coordinate:?} touches numbers on {} rows",
+                            unique_y_coordinates.len()
+                        );
+                    };
+                    let number_on_row = |y| -> eyre::Result<usize> {
+                        let position = vectors
+                            .iter()
+                            .find(|v| v.1 == y)
+                            .and_then(|v| coordinate + *v)
+                            .ok_or(eyre!(
+                                "Day 3 part 2: no number next to gear at {coordinate:?}"
+                            ))?;
+                        Ok(find_number_in_grid_from(&grid, position))
+                    };
 
-                    output.push(
-                        find_number_in_grid_from(&grid, (coordinate + *first).unwrap())
-                            * find_number_in_grid_from(&grid, (coordinate + *second).unwrap()),
-                    );
+                    output.push(number_on_row(y1)? * number_on_row(y2)?);
                 }
             }
             _ => {}
@@ -201,22 +236,18 @@ fn bitfield_to_vectorfield(bitfield: [u32; 3]) -> Vec<Vector> {
 }
 
 fn find_number_in_grid_from(grid: &Grid, pos: Coordinate) -> usize {
+    let is_digit = |c: &Coordinate| matches!(grid.get(*c), Some(Cell::Digit(_)));
     let mut pointer = pos;
-    loop {
-        if (pointer + Vector(-1, 0)).is_some_and(|c| matches!(grid.get(c), Some(Cell::Digit(_)))) {
-            pointer = (pointer + Vector(-1, 0)).unwrap()
-        } else {
-            break;
-        }
+    while let Some(left) = (pointer + Vector(-1, 0)).filter(is_digit) {
+        pointer = left
     }
 
     let mut digits = vec![];
-    loop {
-        if let Some(Cell::Digit(digit)) = grid.get(pointer) {
-            pointer = (pointer + Vector(1, 0)).unwrap();
-            digits.insert(0, digit)
-        } else {
-            break;
+    while let Some(Cell::Digit(digit)) = grid.get(pointer) {
+        digits.insert(0, digit);
+        match pointer + Vector(1, 0) {
+            Some(next) => pointer = next,
+            None => break,
         }
     }
 
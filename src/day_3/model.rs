@@ -1,8 +1,10 @@
-use eyre::Error;
+use eyre::{Error, WrapErr};
 use std::fmt::{Display, Formatter, Write};
 use std::ops::Add;
 use std::str::FromStr;
 
+use crate::parse::{Line, Source};
+
 pub(super) struct Grid {
     pub width: usize,
     pub height: usize,
@@ -10,6 +12,16 @@ pub(super) struct Grid {
 }
 
 impl Grid {
+    /// Builds a grid from cells laid out row by row
+    pub(super) fn from_cells(width: usize, model: Vec<Cell>) -> Self {
+        let height = model.chunks(width).count();
+        Self {
+            width,
+            height,
+            model,
+        }
+    }
+
     pub(super) fn get(&self, index: Coordinate) -> Option<Cell> {
         self.model.get(index.0 + (self.width * index.1)).copied()
     }
@@ -26,35 +38,48 @@ impl Grid {
             .into_iter()
             .filter_map(|c| c)
             .map(|c| Coordinate(c.0 - upper_left_coordinate.0, c.1 - upper_left_coordinate.1));
-        dbg!(coordinate, upper_left_coordinate);
         let mut bitfield = [0b000, 0b000, 0b000];
         for relative_coordinate in relative_coordinates {
             if let Some(Cell::Digit(_)) = self.get(upper_left_coordinate + relative_coordinate) {
                 bitfield[relative_coordinate.1] |= 1 << (2 - relative_coordinate.0)
             }
         }
-        dbg!(bitfield);
         bitfield
     }
 }
 
-impl FromStr for Grid {
-    type Err = Error;
+impl Grid {
+    /// One row of cells per non-blank line, each as wide as the first
+    pub(super) fn parse(source: Source) -> eyre::Result<Self> {
+        let rows = source
+            .lines()
+            .filter(|line| !line.is_blank())
+            .collect::<Vec<_>>();
+
+        // one cell per char, symbols aren't necessarily a single byte
+        let cells = |row: &Line| row.text.chars().count();
+        let width = cells(
+            rows.first()
+                .ok_or_else(|| source.end().error("Grid input is empty"))
+                .wrap_err("Day 3: invalid grid")?,
+        );
+        if let Some(row) = rows.iter().find(|row| cells(row) != width) {
+            return Err(row
+                .error(format!("Row has {} cells, expected {width}", cells(row)))
+                .wrap_err("Day 3: invalid grid"));
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.trim().lines();
-
-        let model: Vec<Cell> = lines
-            .flat_map(|line| line.trim().chars())
-            .map(|char| match char {
-                '0'..='9' => Cell::Digit(char.to_digit(10).unwrap() as usize),
-                '.' => Cell::Empty,
-                '*' => Cell::Gear,
-                char => Cell::Symbol(char),
+        let model: Vec<Cell> = rows
+            .iter()
+            .flat_map(|line| line.text.chars())
+            .map(|char| match char.to_digit(10) {
+                Some(digit) => Cell::Digit(digit as usize),
+                None if char == '.' => Cell::Empty,
+                None if char == '*' => Cell::Gear,
+                None => Cell::Symbol(char),
             })
             .collect();
 
-        let width = s.trim().lines().next().unwrap().len();
         let height = model.chunks(width).count();
         return Ok(Self {
             width,
@@ -64,6 +89,14 @@ impl FromStr for Grid {
     }
 }
 
+impl FromStr for Grid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(Source::from(s))
+    }
+}
+
 impl Display for Grid {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for (idx, line) in self.model.chunks(self.width).enumerate() {
@@ -141,3 +174,37 @@ impl Coordinate {
         todo!()
     }
 }
+
+#[test]
+fn malformed_grids() {
+    let error = Grid::parse(Source::named("grid.txt", "")).err().unwrap();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 3: invalid grid: grid.txt:1:1: Grid input is empty\n  |\n1 | \n  | ^"
+    );
+
+    let error = "12.\n 1.\n..#".parse::<Grid>().err().unwrap();
+    let diagnostic = error
+        .root_cause()
+        .downcast_ref::<crate::diagnostic::Diagnostic>()
+        .unwrap();
+    assert_eq!(diagnostic.message, "Row has 2 cells, expected 3");
+    assert_eq!((diagnostic.line, diagnostic.span.clone()), (2, 1..3));
+
+    // multi-byte symbols are still one cell each
+    let grid = "1é.\n.*€".parse::<Grid>().unwrap();
+    assert_eq!((grid.width, grid.height), (3, 2));
+    assert!(matches!(
+        grid.get(Coordinate(1, 0)),
+        Some(Cell::Symbol('é'))
+    ));
+    assert!(matches!(
+        grid.get(Coordinate(2, 1)),
+        Some(Cell::Symbol('€'))
+    ));
+    let error = "1é.\n..".parse::<Grid>().err().unwrap();
+    assert!(
+        format!("{error:#}").contains("Row has 2 cells, expected 3"),
+        "{error:#}"
+    );
+}
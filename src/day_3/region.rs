@@ -0,0 +1,275 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::model::{Cell, Coordinate, Grid, Vector};
+
+/// Which neighbors of a cell count as connected when growing a region.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum Adjacency {
+    /// Only left and right, used for numbers that read along a row
+    Row,
+    /// Up, down, left and right
+    Orthogonal,
+    /// All 8 surrounding cells
+    Moore,
+}
+
+impl Adjacency {
+    fn vectors(self) -> &'static [Vector] {
+        match self {
+            Adjacency::Row => &[Vector(-1, 0), Vector(1, 0)],
+            Adjacency::Orthogonal => &[Vector(0, -1), Vector(-1, 0), Vector(1, 0), Vector(0, 1)],
+            Adjacency::Moore => &[
+                Vector(-1, -1),
+                Vector(0, -1),
+                Vector(1, -1),
+                Vector(-1, 0),
+                Vector(1, 0),
+                Vector(-1, 1),
+                Vector(0, 1),
+                Vector(1, 1),
+            ],
+        }
+    }
+}
+
+/// A connected set of cells. Cells are kept in row-major order so a
+/// row-adjacent component reads left to right.
+#[derive(Debug, Clone)]
+pub(super) struct Component {
+    pub cells: Vec<Coordinate>,
+    /// Upper left and lower right corners, inclusive
+    pub bounds: (Coordinate, Coordinate),
+}
+
+impl Component {
+    pub(super) fn size(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+impl Grid {
+    pub(super) fn coordinates(&self) -> impl Iterator<Item = Coordinate> + Clone {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coordinate(x, y)))
+    }
+
+    fn neighbors_within(
+        &self,
+        coordinate: Coordinate,
+        adjacency: Adjacency,
+    ) -> impl Iterator<Item = Coordinate> + '_ {
+        adjacency
+            .vectors()
+            .iter()
+            .filter_map(move |v| coordinate + *v)
+            .filter(|c| c.0 < self.width && c.1 < self.height)
+    }
+
+    /// Collects every cell reachable from `seed` through cells where `passable` holds.
+    /// The seed itself has to be passable, otherwise the fill is empty.
+    pub(super) fn flood_fill(
+        &self,
+        seed: Coordinate,
+        adjacency: Adjacency,
+        passable: impl Fn(Cell) -> bool,
+    ) -> HashSet<Coordinate> {
+        let mut seen = HashSet::new();
+        if !self.get(seed).is_some_and(&passable) {
+            return seen;
+        }
+
+        let mut queue = VecDeque::from([seed]);
+        seen.insert(seed);
+        while let Some(current) = queue.pop_front() {
+            for next in self.neighbors_within(current, adjacency) {
+                if self.get(next).is_some_and(&passable) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Labels every connected region of passable cells. Components come out
+    /// in the row-major order of their first cell.
+    pub(super) fn label_components(
+        &self,
+        adjacency: Adjacency,
+        passable: impl Fn(Cell) -> bool,
+    ) -> Vec<Component> {
+        let mut visited: HashSet<Coordinate> = HashSet::new();
+        let mut output = vec![];
+
+        for coordinate in self.coordinates() {
+            if visited.contains(&coordinate) {
+                continue;
+            }
+
+            let region = self.flood_fill(coordinate, adjacency, &passable);
+            if region.is_empty() {
+                continue;
+            }
+
+            let mut cells: Vec<Coordinate> = region.into_iter().collect();
+            cells.sort_by_key(|c| (c.1, c.0));
+            let bounds = cells.iter().fold((coordinate, coordinate), |(min, max), c| {
+                (
+                    Coordinate(min.0.min(c.0), min.1.min(c.1)),
+                    Coordinate(max.0.max(c.0), max.1.max(c.1)),
+                )
+            });
+            visited.extend(cells.iter().copied());
+            output.push(Component { cells, bounds });
+        }
+
+        output
+    }
+}
+
+/// Where a cell sits relative to a closed loop
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum Side {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+/// A closed loop through cell centers, given as its ordered vertices.
+/// The last vertex connects back to the first.
+pub(super) struct Loop(pub Vec<Coordinate>);
+
+impl Loop {
+    fn edges(&self) -> impl Iterator<Item = (Coordinate, Coordinate)> + '_ {
+        self.0
+            .iter()
+            .copied()
+            .zip(self.0.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the enclosed area, using the shoelace formula
+    pub(super) fn double_area(&self) -> usize {
+        let sum = self.edges().fold(0isize, |acc, (a, b)| {
+            acc + (a.0 as isize * b.1 as isize) - (b.0 as isize * a.1 as isize)
+        });
+        sum.unsigned_abs()
+    }
+
+    /// Number of cells the loop passes through, counting every cell along each edge
+    pub(super) fn boundary_count(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| a.0.abs_diff(b.0).max(a.1.abs_diff(b.1)))
+            .sum()
+    }
+
+    /// Cells strictly inside the loop, from Pick's theorem: A = i + b/2 - 1
+    pub(super) fn interior_count(&self) -> usize {
+        (self.double_area() + 2 - self.boundary_count()) / 2
+    }
+
+    /// Classifies a single cell by casting a ray to the right and counting the
+    /// vertical edges it crosses. Edges are half-open on y so vertices aren't counted twice.
+    pub(super) fn classify(&self, point: Coordinate) -> Side {
+        let mut crossings = 0;
+        for (a, b) in self.edges() {
+            let on_segment = (a.0.min(b.0)..=a.0.max(b.0)).contains(&point.0)
+                && (a.1.min(b.1)..=a.1.max(b.1)).contains(&point.1)
+                && (b.0 as isize - a.0 as isize) * (point.1 as isize - a.1 as isize)
+                    == (b.1 as isize - a.1 as isize) * (point.0 as isize - a.0 as isize);
+            if on_segment {
+                return Side::Boundary;
+            }
+
+            if (a.1 > point.1) != (b.1 > point.1) {
+                // x of the edge at the height of the point, compared without division
+                let dy = b.1 as isize - a.1 as isize;
+                let lhs = (point.0 as isize - a.0 as isize) * dy;
+                let rhs = (b.0 as isize - a.0 as isize) * (point.1 as isize - a.1 as isize);
+                if (dy > 0 && lhs < rhs) || (dy < 0 && lhs > rhs) {
+                    crossings += 1;
+                }
+            }
+        }
+
+        if crossings % 2 == 1 {
+            Side::Inside
+        } else {
+            Side::Outside
+        }
+    }
+}
+
+#[test]
+fn flood_fill_stops_at_walls() {
+    let grid: Grid = r"
+    ..#..
+    ..#..
+    ##...
+    .....
+    "
+    .parse()
+    .unwrap();
+
+    let empty = |c| matches!(c, Cell::Empty);
+    assert_eq!(grid.flood_fill(Coordinate(0, 0), Adjacency::Orthogonal, empty).len(), 4);
+    assert_eq!(grid.flood_fill(Coordinate(4, 0), Adjacency::Orthogonal, empty).len(), 12);
+    // diagonal moves slip through the corner from (1, 1) to (2, 2)
+    assert_eq!(grid.flood_fill(Coordinate(0, 0), Adjacency::Moore, empty).len(), 16);
+    assert!(grid.flood_fill(Coordinate(2, 0), Adjacency::Orthogonal, empty).is_empty());
+}
+
+#[test]
+fn labels_digit_runs() {
+    let grid: Grid = r"
+    467..114..
+    ...*......
+    ..35..633.
+    "
+    .parse()
+    .unwrap();
+
+    let components = grid.label_components(Adjacency::Row, |c| matches!(c, Cell::Digit(_)));
+    let summary = components
+        .iter()
+        .map(|c| (c.size(), c.bounds))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            (3, (Coordinate(0, 0), Coordinate(2, 0))),
+            (3, (Coordinate(5, 0), Coordinate(7, 0))),
+            (2, (Coordinate(2, 2), Coordinate(3, 2))),
+            (3, (Coordinate(6, 2), Coordinate(8, 2))),
+        ]
+    );
+}
+
+#[test]
+fn loop_inside_outside() {
+    // a 5x5 square of boundary cells with a notch cut into the right side
+    let shape = Loop(vec![
+        Coordinate(0, 0),
+        Coordinate(4, 0),
+        Coordinate(4, 1),
+        Coordinate(2, 1),
+        Coordinate(2, 3),
+        Coordinate(4, 3),
+        Coordinate(4, 4),
+        Coordinate(0, 4),
+    ]);
+
+    assert_eq!(shape.boundary_count(), 20);
+    assert_eq!(shape.interior_count(), 3);
+    assert_eq!(shape.classify(Coordinate(1, 2)), Side::Inside);
+    assert_eq!(shape.classify(Coordinate(3, 2)), Side::Outside);
+    assert_eq!(shape.classify(Coordinate(2, 2)), Side::Boundary);
+    assert_eq!(shape.classify(Coordinate(0, 3)), Side::Boundary);
+    assert_eq!(shape.classify(Coordinate(5, 0)), Side::Outside);
+
+    let inside = (0..5)
+        .flat_map(|y| (0..5).map(move |x| Coordinate(x, y)))
+        .filter(|c| shape.classify(*c) == Side::Inside)
+        .count();
+    assert_eq!(inside, shape.interior_count());
+}
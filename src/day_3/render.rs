@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use super::model::{Cell, Coordinate, Grid};
+
+/// Foreground color for a single rendered cell
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum Style {
+    Plain,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Dim,
+}
+
+impl Style {
+    fn ansi_code(self) -> Option<&'static str> {
+        match self {
+            Style::Plain => None,
+            Style::Red => Some("\x1b[31m"),
+            Style::Green => Some("\x1b[32m"),
+            Style::Yellow => Some("\x1b[33m"),
+            Style::Blue => Some("\x1b[34m"),
+            Style::Dim => Some("\x1b[2m"),
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// A grid paired with a styling function. Displaying it writes the grid
+/// with ANSI colors, or the plain text when color is switched off.
+pub(super) struct Rendered<'a, F> {
+    grid: &'a Grid,
+    style: F,
+    color: bool,
+}
+
+impl<'a, F> Rendered<'a, F>
+where
+    F: Fn(Coordinate, Cell) -> Style,
+{
+    pub(super) fn plain(self) -> Self {
+        Self {
+            color: false,
+            ..self
+        }
+    }
+}
+
+impl Grid {
+    /// Render with a style picked per cell
+    pub(super) fn render<F>(&self, style: F) -> Rendered<'_, F>
+    where
+        F: Fn(Coordinate, Cell) -> Style,
+    {
+        Rendered {
+            grid: self,
+            style,
+            color: true,
+        }
+    }
+
+    /// Render with every coordinate in the set drawn in `style`
+    pub(super) fn highlight<'a>(
+        &'a self,
+        coordinates: &'a HashSet<Coordinate>,
+        style: Style,
+    ) -> Rendered<'a, impl Fn(Coordinate, Cell) -> Style + 'a> {
+        self.render(move |c, _| {
+            if coordinates.contains(&c) {
+                style
+            } else {
+                Style::Plain
+            }
+        })
+    }
+}
+
+impl<'a, F> Display for Rendered<'a, F>
+where
+    F: Fn(Coordinate, Cell) -> Style,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.grid.height {
+            for x in 0..self.grid.width {
+                let coordinate = Coordinate(x, y);
+                let Some(cell) = self.grid.get(coordinate) else {
+                    continue;
+                };
+
+                match (self.color, (self.style)(coordinate, cell).ansi_code()) {
+                    (true, Some(code)) => write!(f, "{code}{cell}{RESET}")?,
+                    _ => Display::fmt(&cell, f)?,
+                }
+            }
+
+            if y < self.grid.height - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn plain_render_matches_display() {
+    let grid: Grid = r"
+    467..114..
+    ...*......
+    "
+    .parse()
+    .unwrap();
+
+    let rendered = grid.render(|_, _| Style::Red).plain().to_string();
+    assert_eq!(rendered, grid.to_string());
+}
+
+#[test]
+fn highlights_coordinates() {
+    let grid: Grid = r"
+    1.
+    .*
+    "
+    .parse()
+    .unwrap();
+
+    let marked = HashSet::from([Coordinate(1, 1)]);
+    assert_eq!(
+        grid.highlight(&marked, Style::Yellow).to_string(),
+        "1.\n.\x1b[33m*\x1b[0m"
+    );
+}
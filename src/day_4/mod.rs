@@ -1,73 +1,599 @@
-use std::collections::HashSet;
+use eyre::{bail, eyre, Context};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::str::FromStr;
 
-#[derive(Copy, Clone)]
+use crate::parse::Line;
+use crate::stream::try_for_each_line;
+use scoring::*;
+use validate::*;
+
+#[derive(Clone, Debug)]
 struct Card {
     id: usize,
-    numbers: &'static [usize],
-    winners: &'static [usize],
+    numbers: Cow<'static, [usize]>,
+    winners: Cow<'static, [usize]>,
+}
+
+impl Card {
+    const fn new(id: usize, numbers: &'static [usize], winners: &'static [usize]) -> Self {
+        Self {
+            id,
+            numbers: Cow::Borrowed(numbers),
+            winners: Cow::Borrowed(winners),
+        }
+    }
 }
 
+impl Card {
+    /// A `Card N: numbers | winners` line
+    fn parse(line: Line) -> eyre::Result<Self> {
+        let (id, lists) = line.labeled("Card")?;
+        let (numbers, winners) = lists.split_pair('|')?;
+
+        Ok(Self {
+            id,
+            numbers: Cow::Owned(numbers.numbers()?),
+            winners: Cow::Owned(winners.numbers()?),
+        })
+    }
+}
+
+impl FromStr for Card {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(Line::new(1, s))
+    }
+}
+
+/// Parses one card per line. With `validate` set the deck is also checked
+/// for malformed data, and every problem found is reported at once.
+fn parse_cards(input: &str, validate: bool) -> eyre::Result<Vec<Card>> {
+    let mut cards = vec![];
+    for_each_card(input.as_bytes(), |card| {
+        cards.push(card);
+        Ok(())
+    })?;
+
+    if validate {
+        let problems = validate_cards(&cards);
+        if !problems.is_empty() {
+            let report = problems
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            bail!("Found {} problems in the deck:\n{report}", problems.len());
+        }
+    }
+
+    Ok(cards)
+}
+
+/// Parses the cards of `reader` one line at a time, skipping blank lines
+fn for_each_card(
+    reader: impl BufRead,
+    mut f: impl FnMut(Card) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    try_for_each_line(reader, |number, text| {
+        let line = Line::new(number, text);
+        if line.is_blank() {
+            return Ok(());
+        }
+        let card =
+            Card::parse(line).with_context(|| format!("Day 4: invalid card on line {number}"))?;
+        f(card)
+    })
+}
+
+mod scoring;
 mod test_source;
+mod validate;
 
-fn compute_sum_of_cards(cards: &[Card]) -> usize {
-    let mut sum = 0;
-    for game in cards {
-        let choices: HashSet<&usize> = HashSet::from_iter(game.numbers);
-        let winners: HashSet<&usize> = HashSet::from_iter(game.winners);
+/// Numbers below this fit in the `u128` bitset, larger ones take the slow path
+const BITSET_LIMIT: usize = 128;
+
+fn bitset(numbers: &[usize]) -> u128 {
+    numbers.iter().fold(0, |set, n| set | (1 << n))
+}
+
+/// Counts the distinct numbers a card has in common with its winners
+fn count_matches(card: &Card) -> usize {
+    let fits = |numbers: &[usize]| numbers.iter().all(|n| *n < BITSET_LIMIT);
+    if fits(&card.numbers) && fits(&card.winners) {
+        return (bitset(&card.numbers) & bitset(&card.winners)).count_ones() as usize;
+    }
+
+    // sorted merge intersection
+    let sorted = |numbers: &[usize]| {
+        let mut numbers = numbers.to_vec();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers
+    };
+    let (numbers, winners) = (sorted(&card.numbers), sorted(&card.winners));
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < numbers.len() && j < winners.len() {
+        match numbers[i].cmp(&winners[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+fn match_counts(cards: &[Card]) -> Vec<usize> {
+    cards.iter().map(count_matches).collect()
+}
 
-        let count_common = winners.intersection(&choices).count();
+fn score_card(card: &Card, matches: usize, scoring: &impl Scoring) -> eyre::Result<usize> {
+    scoring.score(matches).ok_or_else(|| {
+        eyre!(
+            "Card {} with {matches} matches scores more points than fit in a usize",
+            card.id
+        )
+    })
+}
+
+/// Points for every card, keyed by card id in deck order
+fn score_cards(cards: &[Card], scoring: &impl Scoring) -> eyre::Result<Vec<(usize, usize)>> {
+    cards
+        .iter()
+        .zip(match_counts(cards))
+        .map(|(card, matches)| Ok((card.id, score_card(card, matches, scoring)?)))
+        .collect()
+}
+
+fn add_score(sum: usize, id: usize, score: usize) -> eyre::Result<usize> {
+    sum.checked_add(score)
+        .ok_or_else(|| eyre!("The total score overflows a usize at card {id}"))
+}
+
+fn compute_sum_of_cards(cards: &[Card], scoring: &impl Scoring) -> eyre::Result<usize> {
+    score_cards(cards, scoring)
+        .context("Day 4 part 1: could not score the deck")?
+        .into_iter()
+        .try_fold(0, |sum, (id, score)| add_score(sum, id, score))
+        .context("Day 4 part 1: could not score the deck")
+}
+
+/// How a single card ended up with the copies it has at the end of the cascade
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct CardCopies {
+    id: usize,
+    matches: usize,
+    /// Total number of instances, the original included
+    copies: usize,
+    /// Earlier card ids and how many copies each one handed to this card
+    won_from: Vec<(usize, usize)>,
+}
 
-        if count_common > 0 {
-            sum += 2usize.pow(count_common as u32 - 1)
+impl Display for CardCopies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Card {}: {} matches, {} copies",
+            self.id, self.matches, self.copies
+        )?;
+        for (index, (id, copies)) in self.won_from.iter().enumerate() {
+            let separator = if index == 0 { " <- " } else { ", " };
+            write!(f, "{separator}{copies} from card {id}")?;
         }
+        Ok(())
     }
+}
 
-    sum
+/// What to do when a card wins copies of cards past the end of the deck
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum DeckEnd {
+    /// Drop the copies that would land past the last card
+    Clamp,
+    /// Refuse to play the deck
+    Error,
+    /// Continue counting from the first card. Cards that were already played
+    /// keep the extra copies but don't hand out any more of their own.
+    Wrap,
 }
 
-fn do_outrageous_things(cards: &[Card]) -> usize {
-    let mut local_copy = cards
+/// Plays out the cascade and records where every copy came from, in deck
+/// order. Ids aren't necessarily unique, so they can't be used as keys.
+fn explain_outrageous_things(cards: &[Card], deck_end: DeckEnd) -> eyre::Result<Vec<CardCopies>> {
+    let mut breakdown = cards
         .iter()
-        .copied()
-        .map(|c| (c, 1usize))
+        .zip(match_counts(cards))
+        .map(|(card, matches)| CardCopies {
+            id: card.id,
+            matches,
+            copies: 1,
+            won_from: vec![],
+        })
         .collect::<Vec<_>>();
-    let mut index = 0;
-    loop {
-        if index == local_copy.len() {
-            break;
-        }
-        let (game, copies) = local_copy[index];
-        let choices: HashSet<&usize> = HashSet::from_iter(game.numbers);
-        let winners: HashSet<&usize> = HashSet::from_iter(game.winners);
 
-        let count_common = winners.intersection(&choices).count();
+    let deck_size = breakdown.len();
+    for index in 0..deck_size {
+        let (id, matches, copies) = {
+            let card = &breakdown[index];
+            (card.id, card.matches, card.copies)
+        };
         let next_index = index + 1;
-        for next_index in next_index..(next_index + count_common) {
-            let handle = local_copy.get_mut(next_index).unwrap();
-            handle.1 += copies
+        for next_index in next_index..(next_index + matches) {
+            let next_index = match deck_end {
+                _ if next_index < deck_size => next_index,
+                DeckEnd::Clamp => break,
+                DeckEnd::Error => bail!(
+                    "Card {id} wins {matches} copies but only {} cards follow it",
+                    deck_size - index - 1
+                ),
+                DeckEnd::Wrap => next_index % deck_size,
+            };
+            let handle = &mut breakdown[next_index];
+            handle.copies += copies;
+            handle.won_from.push((id, copies));
         }
-        index += 1;
     }
 
-    local_copy.iter().fold(0, |acc, (_v, copies)| acc + copies)
+    Ok(breakdown)
+}
+
+fn do_outrageous_things(cards: &[Card], deck_end: DeckEnd) -> eyre::Result<usize> {
+    Ok(explain_outrageous_things(cards, deck_end)
+        .context("Day 4 part 2: could not play out the deck")?
+        .iter()
+        .fold(0, |acc, card| acc + card.copies))
+}
+
+/// [`compute_sum_of_cards`] over a reader, scoring each card as it's read.
+/// The deck isn't validated since that needs every card at once.
+fn sum_of_cards_from(reader: impl BufRead, scoring: &impl Scoring) -> eyre::Result<usize> {
+    let mut sum = 0;
+    for_each_card(reader, |card| {
+        let score = score_card(&card, count_matches(&card), scoring)?;
+        sum = add_score(sum, card.id, score)?;
+        Ok(())
+    })
+    .context("Day 4 part 1: could not score the deck")?;
+    Ok(sum)
+}
+
+/// [`do_outrageous_things`] over a reader, one card at a time. Only the copies
+/// already won by the cards still to come are kept, so memory grows with the
+/// most matches on a card rather than with the deck. Wrapping hands copies
+/// back to cards long gone, so it's refused.
+fn outrageous_things_from(reader: impl BufRead, deck_end: DeckEnd) -> eyre::Result<usize> {
+    play_streamed(reader, deck_end).context("Day 4 part 2: could not play out the deck")
+}
+
+fn play_streamed(reader: impl BufRead, deck_end: DeckEnd) -> eyre::Result<usize> {
+    if deck_end == DeckEnd::Wrap {
+        bail!("Wrapping around the deck needs every card in memory");
+    }
+
+    // extra copies won so far by the cards following the current one
+    let mut pending = VecDeque::<usize>::new();
+    // (index, id, matches) of the cards reaching further than any card before
+    // them that could still run past the end of the deck
+    let mut furthest = VecDeque::<(usize, usize, usize)>::new();
+    let (mut deck_size, mut total) = (0, 0);
+
+    for_each_card(reader, |card| {
+        let index = deck_size;
+        deck_size += 1;
+
+        let copies = 1 + pending.pop_front().unwrap_or(0);
+        total += copies;
+        let matches = count_matches(&card);
+        if pending.len() < matches {
+            pending.resize(matches, 0);
+        }
+        pending.iter_mut().take(matches).for_each(|p| *p += copies);
+
+        while furthest.front().is_some_and(|(i, _, m)| i + m <= index) {
+            furthest.pop_front();
+        }
+        if furthest
+            .back()
+            .is_none_or(|(i, _, m)| index + matches > i + m)
+        {
+            furthest.push_back((index, card.id, matches));
+        }
+        Ok(())
+    })?;
+
+    if deck_end == DeckEnd::Error {
+        if let Some((index, id, matches)) =
+            furthest.into_iter().find(|(i, _, m)| i + m >= deck_size)
+        {
+            bail!(
+                "Card {id} wins {matches} copies but only {} cards follow it",
+                deck_size - index - 1
+            );
+        }
+    }
+    Ok(total)
 }
 
 #[test]
 fn test_data() {
-    assert_eq!(compute_sum_of_cards(test_source::TEST_DATA), 13)
+    assert_eq!(
+        compute_sum_of_cards(&test_source::TEST_DATA, &Doubling).unwrap(),
+        13
+    )
 }
 
 #[test]
 fn part_1() {
-    assert_eq!(compute_sum_of_cards(test_source::PROBLEM_DATA), 25004)
+    assert_eq!(
+        compute_sum_of_cards(&test_source::PROBLEM_DATA, &Doubling).unwrap(),
+        25004
+    )
+}
+
+#[test]
+fn per_card_scores() {
+    let doubling = score_cards(&test_source::TEST_DATA, &Doubling).unwrap();
+    assert_eq!(
+        doubling,
+        vec![(1, 8), (2, 2), (3, 2), (4, 1), (5, 0), (6, 0)]
+    );
+
+    assert_eq!(
+        compute_sum_of_cards(&test_source::TEST_DATA, &Linear(1)).unwrap(),
+        9
+    );
+    assert_eq!(
+        compute_sum_of_cards(&test_source::TEST_DATA, &Fibonacci).unwrap(),
+        6
+    );
+
+    // a card with 70 matches is worth 2^69 points
+    let numbers = (1..=70)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input = format!("Card 1: {numbers} | {numbers}\n");
+    let error = sum_of_cards_from(input.as_bytes(), &Doubling).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 4 part 1: could not score the deck: Card 1 with 70 matches scores more points than fit in a usize"
+    );
+    let cards = parse_cards(&input, true).unwrap();
+    assert!(compute_sum_of_cards(&cards, &Doubling).is_err());
+    assert_eq!(compute_sum_of_cards(&cards, &Linear(2)).unwrap(), 140);
+
+    // each card fits on its own, but not all of them together
+    let cards = parse_cards(&input.repeat(2), false).unwrap();
+    let error = compute_sum_of_cards(&cards, &Linear(usize::MAX / 100)).unwrap_err();
+    assert_eq!(
+        error.root_cause().to_string(),
+        "The total score overflows a usize at card 1"
+    );
 }
 
 #[test]
 fn part_2_test() {
-    assert_eq!(do_outrageous_things(test_source::TEST_DATA), 30)
+    assert_eq!(
+        do_outrageous_things(&test_source::TEST_DATA, DeckEnd::Error).unwrap(),
+        30
+    )
 }
 
 #[test]
 fn part_2() {
-    assert_eq!(do_outrageous_things(test_source::PROBLEM_DATA), 14427616)
+    assert_eq!(
+        do_outrageous_things(&test_source::PROBLEM_DATA, DeckEnd::Error).unwrap(),
+        14427616
+    )
+}
+
+#[test]
+fn part_2_breakdown() {
+    let breakdown = explain_outrageous_things(&test_source::TEST_DATA, DeckEnd::Error).unwrap();
+
+    let copies = breakdown
+        .iter()
+        .map(|c| (c.id, c.matches, c.copies))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        copies,
+        vec![
+            (1, 4, 1),
+            (2, 2, 2),
+            (3, 2, 4),
+            (4, 1, 8),
+            (5, 0, 14),
+            (6, 0, 1)
+        ]
+    );
+    assert_eq!(breakdown[4].won_from, vec![(1, 1), (3, 4), (4, 8)]);
+    assert_eq!(
+        breakdown[2].to_string(),
+        "Card 3: 2 matches, 4 copies <- 1 from card 1, 2 from card 2"
+    );
+}
+
+#[test]
+fn last_card_with_matches() {
+    let deck: &[Card] = &[
+        Card::new(1, &[1, 2], &[1]),
+        Card::new(2, &[1, 2, 3], &[1, 2]),
+        Card::new(3, &[4, 5], &[4, 5]),
+    ];
+
+    // card 1 wins a copy of card 2, both instances of card 2 win a copy of card 3
+    // and everything else points past the end of the deck
+    assert_eq!(
+        do_outrageous_things(deck, DeckEnd::Clamp).unwrap(),
+        1 + 2 + 3
+    );
+    assert!(do_outrageous_things(deck, DeckEnd::Error).is_err());
+
+    // wrapping around, card 2 also hands 2 copies to card 1 and card 3 hands 3 each to cards 1 and 2
+    let wrapped = explain_outrageous_things(deck, DeckEnd::Wrap).unwrap();
+    let copies = wrapped.iter().map(|c| c.copies).collect::<Vec<_>>();
+    assert_eq!(copies, vec![1 + 2 + 3, 2 + 3, 3]);
+    assert_eq!(wrapped[0].won_from, vec![(2, 2), (3, 3)]);
+}
+
+#[test]
+fn duplicate_ids_are_separate_cards() {
+    let deck: &[Card] = &[
+        Card::new(1, &[1], &[1]),
+        Card::new(1, &[2], &[3]),
+        Card::new(2, &[4], &[5]),
+    ];
+
+    let breakdown = explain_outrageous_things(deck, DeckEnd::Clamp).unwrap();
+    let copies = breakdown
+        .iter()
+        .map(|c| (c.id, c.copies))
+        .collect::<Vec<_>>();
+    assert_eq!(copies, vec![(1, 1), (1, 2), (2, 1)]);
+    assert_eq!(do_outrageous_things(deck, DeckEnd::Clamp).unwrap(), 4);
+    assert_eq!(
+        do_outrageous_things(deck, DeckEnd::Clamp).unwrap(),
+        outrageous_things_from(
+            "Card 1: 1 | 1\nCard 1: 2 | 3\nCard 2: 4 | 5\n".as_bytes(),
+            DeckEnd::Clamp
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn runtime_parser() {
+    let input = r"
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    ";
+
+    let cards = parse_cards(input, true).unwrap();
+    assert_eq!(compute_sum_of_cards(&cards, &Doubling).unwrap(), 13);
+
+    let broken = input.replace("Card 4: 41", "Card 2: 41");
+    assert!(parse_cards(&broken, false).is_ok());
+    let error = parse_cards(&broken, true).unwrap_err().to_string();
+    // the input starts with a blank line, so the fourth card is on line 5
+    assert!(
+        error.contains("Card 2 (deck position 3): duplicate card id 2"),
+        "{error}"
+    );
+
+    let broken = input.replace("Card 3:", "Card 3");
+    let error = parse_cards(&broken, false).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 4: invalid card on line 4: line 4, column 1: Expected `Card N:`
+  |
+4 | Card 3  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+  | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"
+    );
+}
+
+#[test]
+fn streamed_decks() {
+    use crate::stream::Repeated;
+
+    let input = "
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    ";
+    assert_eq!(sum_of_cards_from(input.as_bytes(), &Doubling).unwrap(), 13);
+    assert_eq!(
+        outrageous_things_from(input.as_bytes(), DeckEnd::Error).unwrap(),
+        30
+    );
+
+    // the same deck as `last_card_with_matches`
+    let deck = "Card 1: 1 2 | 1\nCard 2: 1 2 3 | 1 2\nCard 3: 4 5 | 4 5\n";
+    assert_eq!(
+        outrageous_things_from(deck.as_bytes(), DeckEnd::Clamp).unwrap(),
+        1 + 2 + 3
+    );
+    let error = outrageous_things_from(deck.as_bytes(), DeckEnd::Error).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 4 part 2: could not play out the deck: Card 2 wins 2 copies but only 1 cards follow it"
+    );
+    assert!(outrageous_things_from(deck.as_bytes(), DeckEnd::Wrap).is_err());
+
+    // every card wins one copy of the next, so card n ends up with n copies
+    let cards = 20_000;
+    let line = "Card 1: 1 2 3 | 3 4 5\n";
+    assert_eq!(
+        sum_of_cards_from(Repeated::new(line, cards), &Doubling).unwrap(),
+        cards
+    );
+    assert_eq!(
+        outrageous_things_from(Repeated::new(line, cards), DeckEnd::Clamp).unwrap(),
+        cards * (cards + 1) / 2
+    );
+}
+
+#[test]
+fn large_numbers_match_like_small_ones() {
+    let small = Card::new(1, &[1, 5, 5, 127, 40], &[5, 127, 3, 1]);
+    let large = Card::new(2, &[1, 5, 5, 1270, 40], &[5, 1270, 3, 1]);
+
+    assert_eq!(count_matches(&small), 3);
+    assert_eq!(count_matches(&large), 3);
+}
+
+/// Timing comparison of the bitset matcher against the per-card `HashSet`
+/// intersection it replaced.
+/// Run with `cargo test --release day_4 -- --ignored --nocapture`
+#[ignore]
+#[test]
+fn benchmark_match_counts() {
+    use std::collections::HashSet;
+    use std::time::Instant;
+
+    // a cheap linear congruential generator keeps the deck reproducible
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (state >> 33) as usize % 99 + 1
+    };
+    let cards: Vec<Card> = (1..=200_000)
+        .map(|id| Card {
+            id,
+            numbers: Cow::Owned((0..10).map(|_| next()).collect()),
+            winners: Cow::Owned((0..25).map(|_| next()).collect()),
+        })
+        .collect();
+
+    let timer = Instant::now();
+    let hashed: Vec<usize> = cards
+        .iter()
+        .map(|card| {
+            let choices: HashSet<&usize> = HashSet::from_iter(card.numbers.iter());
+            let winners: HashSet<&usize> = HashSet::from_iter(card.winners.iter());
+            winners.intersection(&choices).count()
+        })
+        .collect();
+    let hashed_time = timer.elapsed();
+
+    let timer = Instant::now();
+    let counted = match_counts(&cards);
+    let bitset_time = timer.elapsed();
+
+    println!("HashSet: {hashed_time:?}, bitset: {bitset_time:?}");
+    assert_eq!(hashed, counted);
 }
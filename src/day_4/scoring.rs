@@ -0,0 +1,90 @@
+/// Turns the number of matches on a card into the points it's worth,
+/// `None` when that doesn't fit in a `usize`
+pub(super) trait Scoring {
+    fn score(&self, matches: usize) -> Option<usize>;
+}
+
+/// The puzzle's rule: one point for the first match, doubled for every match after
+pub(super) struct Doubling;
+
+impl Scoring for Doubling {
+    fn score(&self, matches: usize) -> Option<usize> {
+        if matches == 0 {
+            Some(0)
+        } else {
+            2usize.checked_pow(u32::try_from(matches - 1).ok()?)
+        }
+    }
+}
+
+/// A fixed number of points per match
+pub(super) struct Linear(pub usize);
+
+impl Scoring for Linear {
+    fn score(&self, matches: usize) -> Option<usize> {
+        matches.checked_mul(self.0)
+    }
+}
+
+/// 0, 1, 1, 2, 3, 5, ... points for 0, 1, 2, 3, 4, 5, ... matches
+pub(super) struct Fibonacci;
+
+impl Scoring for Fibonacci {
+    fn score(&self, matches: usize) -> Option<usize> {
+        if matches == 0 {
+            return Some(0);
+        }
+        // stop at the last term, the one after it may not fit
+        (1..matches)
+            .try_fold((0usize, 1usize), |(a, b), _| Some((b, a.checked_add(b)?)))
+            .map(|(_, b)| b)
+    }
+}
+
+/// Points read from a table indexed by the number of matches.
+/// Anything past the end of the table scores the last entry.
+pub(super) struct LookupTable(pub Vec<usize>);
+
+impl Scoring for LookupTable {
+    fn score(&self, matches: usize) -> Option<usize> {
+        Some(
+            self.0
+                .get(matches)
+                .or(self.0.last())
+                .copied()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[test]
+fn built_in_rules() {
+    let scores = |scoring: &dyn Scoring| {
+        (0..7)
+            .map(|m| scoring.score(m).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(scores(&Doubling), vec![0, 1, 2, 4, 8, 16, 32]);
+    assert_eq!(scores(&Linear(3)), vec![0, 3, 6, 9, 12, 15, 18]);
+    assert_eq!(scores(&Fibonacci), vec![0, 1, 1, 2, 3, 5, 8]);
+    assert_eq!(
+        scores(&LookupTable(vec![0, 10, 50, 100])),
+        vec![0, 10, 50, 100, 100, 100, 100]
+    );
+}
+
+#[test]
+fn scores_too_big_for_a_usize() {
+    let top = usize::BITS as usize;
+    assert_eq!(Doubling.score(top), Some(1 << (top - 1)));
+    assert_eq!(Doubling.score(top + 1), None);
+    assert_eq!(Doubling.score(usize::MAX), None);
+
+    assert_eq!(Linear(2).score(usize::MAX / 2), Some(usize::MAX - 1));
+    assert_eq!(Linear(2).score(usize::MAX / 2 + 1), None);
+
+    // F(93) is the largest Fibonacci number that fits in 64 bits
+    assert_eq!(Fibonacci.score(93), Some(12200160415121876738));
+    assert_eq!(Fibonacci.score(94), None);
+}
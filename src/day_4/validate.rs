@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+use super::Card;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum List {
+    Numbers,
+    Winners,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) enum ProblemKind {
+    /// `number` shows up again at `position` within the list
+    DuplicateNumber {
+        list: List,
+        number: usize,
+        position: usize,
+    },
+    DuplicateId,
+    /// The id isn't larger than the card before it
+    OutOfOrderId {
+        previous: usize,
+    },
+    /// The list doesn't have as many numbers as most of the deck
+    LengthMismatch {
+        list: List,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Something wrong with the card at `index` in the deck
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct Problem {
+    /// 0-based position in the deck, which isn't a line number since blank
+    /// lines are skipped while parsing
+    pub index: usize,
+    pub card_id: usize,
+    pub kind: ProblemKind,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Card {} (deck position {}): ", self.card_id, self.index)?;
+        match &self.kind {
+            ProblemKind::DuplicateNumber {
+                list,
+                number,
+                position,
+            } => write!(f, "{number} repeated in {list:?} at position {position}"),
+            ProblemKind::DuplicateId => write!(f, "duplicate card id {}", self.card_id),
+            ProblemKind::OutOfOrderId { previous } => {
+                write!(f, "card id comes after card {previous}")
+            }
+            ProblemKind::LengthMismatch {
+                list,
+                expected,
+                found,
+            } => write!(f, "{list:?} has {found} entries, expected {expected}"),
+        }
+    }
+}
+
+/// The length most cards in the deck agree on, first seen wins a tie
+fn usual_length(mut lengths: impl Iterator<Item = usize> + Clone) -> Option<usize> {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for length in lengths.clone() {
+        *counts.entry(length).or_default() += 1;
+    }
+    let most = counts.values().max()?;
+    lengths.find(|l| counts[l] == *most)
+}
+
+/// Checks a whole deck and returns every problem found, in deck order
+pub(super) fn validate_cards(cards: &[Card]) -> Vec<Problem> {
+    let expected_numbers = usual_length(cards.iter().map(|c| c.numbers.len()));
+    let expected_winners = usual_length(cards.iter().map(|c| c.winners.len()));
+
+    let mut problems = vec![];
+    let mut seen_ids = HashSet::new();
+    let mut previous_id = None;
+    for (index, card) in cards.iter().enumerate() {
+        let mut report = |kind| {
+            problems.push(Problem {
+                index,
+                card_id: card.id,
+                kind,
+            })
+        };
+
+        if !seen_ids.insert(card.id) {
+            report(ProblemKind::DuplicateId);
+        } else if let Some(previous) = previous_id.filter(|p| *p >= card.id) {
+            report(ProblemKind::OutOfOrderId { previous });
+        }
+        previous_id = Some(card.id);
+
+        for (list, numbers, expected) in [
+            (List::Numbers, &card.numbers, expected_numbers),
+            (List::Winners, &card.winners, expected_winners),
+        ] {
+            let mut seen_numbers = HashSet::new();
+            for (position, number) in numbers.iter().enumerate() {
+                if !seen_numbers.insert(number) {
+                    report(ProblemKind::DuplicateNumber {
+                        list,
+                        number: *number,
+                        position,
+                    });
+                }
+            }
+
+            if let Some(expected) = expected.filter(|e| *e != numbers.len()) {
+                report(ProblemKind::LengthMismatch {
+                    list,
+                    expected,
+                    found: numbers.len(),
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+#[test]
+fn clean_decks_pass() {
+    assert_eq!(validate_cards(&super::test_source::TEST_DATA), vec![]);
+    assert_eq!(validate_cards(&super::test_source::PROBLEM_DATA), vec![]);
+}
+
+#[test]
+fn reports_every_problem() {
+    let deck = [
+        Card::new(1, &[1, 2, 3], &[4, 5]),
+        Card::new(3, &[1, 2, 2], &[4, 5]),
+        Card::new(2, &[1, 2, 3], &[4, 5, 6]),
+        Card::new(3, &[7, 8, 9], &[4, 5]),
+    ];
+
+    let problems = validate_cards(&deck);
+    assert_eq!(
+        problems
+            .iter()
+            .map(|p| (p.index, &p.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                1,
+                &ProblemKind::DuplicateNumber {
+                    list: List::Numbers,
+                    number: 2,
+                    position: 2
+                }
+            ),
+            (2, &ProblemKind::OutOfOrderId { previous: 3 }),
+            (
+                2,
+                &ProblemKind::LengthMismatch {
+                    list: List::Winners,
+                    expected: 2,
+                    found: 3
+                }
+            ),
+            (3, &ProblemKind::DuplicateId),
+        ]
+    );
+    assert_eq!(
+        problems[0].to_string(),
+        "Card 3 (deck position 1): 2 repeated in Numbers at position 2"
+    );
+}
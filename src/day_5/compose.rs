@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use eyre::eyre;
+
+use crate::day_5::input_data::{Key, Maps, Rule};
+use crate::day_5::rules_for;
+
+/// Values in `source` map to `value + offset`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct Segment {
+    pub source: Range<u64>,
+    pub offset: i128,
+}
+
+impl Segment {
+    /// `None` when the result doesn't fit in a `u64`
+    fn apply(&self, value: u64) -> Option<u64> {
+        u64::try_from(value as i128 + self.offset).ok()
+    }
+
+    fn image(&self) -> Option<Range<u64>> {
+        Some(self.apply(self.source.start)?..self.apply(self.source.end)?)
+    }
+}
+
+/// A whole chain of maps collapsed into one. The segments are sorted and
+/// cover every value from 0 up, so a lookup is a single binary search.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct ComposedMap {
+    segments: Vec<Segment>,
+}
+
+/// One map as segments, with the gaps between rules filled in as identity
+fn segments_for(rules: &[Rule]) -> Vec<Segment> {
+    let mut sorted = rules.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|r| r.source().start);
+
+    let mut output = vec![];
+    let mut cursor = 0;
+    for rule in sorted {
+        if rule.source().start > cursor {
+            output.push(Segment {
+                source: cursor..rule.source().start,
+                offset: 0,
+            });
+        }
+        output.push(Segment {
+            source: rule.source().clone(),
+            offset: rule.destination() as i128 - rule.source().start as i128,
+        });
+        cursor = rule.source().end;
+    }
+    output.push(Segment {
+        source: cursor..u64::MAX,
+        offset: 0,
+    });
+    output
+}
+
+impl ComposedMap {
+    pub(super) fn identity() -> Self {
+        Self {
+            segments: vec![Segment {
+                source: 0..u64::MAX,
+                offset: 0,
+            }],
+        }
+    }
+
+    pub(super) fn from_chain(lookup_chain: &[Key], maps: &Maps) -> eyre::Result<Self> {
+        lookup_chain
+            .iter()
+            .try_fold(Self::identity(), |composed, link| {
+                composed.then(link, &segments_for(rules_for(link, maps)?))
+            })
+    }
+
+    /// Feeds the output of this map through `next`, the segments of the `link` map
+    fn then(&self, link: &Key, next: &[Segment]) -> eyre::Result<Self> {
+        let overflow = |range: &Range<u64>| {
+            eyre!(
+                "Composing the {}-to-{} map overflows for values in {range:?}",
+                link.0,
+                link.1
+            )
+        };
+        let mut segments = vec![];
+        for segment in &self.segments {
+            let image = segment.image().ok_or_else(|| overflow(&segment.source))?;
+            for following in next {
+                let (start, end) = (
+                    image.start.max(following.source.start),
+                    image.end.min(following.source.end),
+                );
+                if start >= end {
+                    continue;
+                }
+                let back = |v: u64| {
+                    u64::try_from(v as i128 - segment.offset).map_err(|_| overflow(&segment.source))
+                };
+                segments.push(Segment {
+                    source: back(start)?..back(end)?,
+                    offset: segment.offset + following.offset,
+                });
+            }
+        }
+        segments.sort_by_key(|s| s.source.start);
+        Ok(Self { segments })
+    }
+
+    fn segment_for(&self, value: u64) -> Option<&Segment> {
+        self.segments
+            .binary_search_by(|s| {
+                if s.source.contains(&value) {
+                    Ordering::Equal
+                } else if s.source.end <= value {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .ok()
+            .map(|index| &self.segments[index])
+    }
+
+    /// `None` only if `value` maps past `u64::MAX`, which `from_chain` rules out
+    pub(super) fn get(&self, value: u64) -> Option<u64> {
+        self.segment_for(value)
+            .map_or(Some(value), |s| s.apply(value))
+    }
+
+    /// The smallest value anything in `range` maps to. Each segment is an
+    /// increasing line, so only the first value of each overlap matters.
+    /// `None` when the range is empty or part of it maps past `u64::MAX`.
+    pub(super) fn min_image(&self, range: Range<u64>) -> Option<u64> {
+        let first = self
+            .segments
+            .partition_point(|s| s.source.end <= range.start);
+        self.segments[first..]
+            .iter()
+            .take_while(|s| s.source.start < range.end)
+            .map(|s| s.apply(s.source.start.max(range.start)))
+            .min()
+            .flatten()
+    }
+
+    pub(super) fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl Display for ComposedMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.segments.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:?} -> ", segment.source)?;
+            match segment.image() {
+                Some(image) => write!(f, "{image:?}")?,
+                None => write!(f, "overflow")?,
+            }
+            write!(f, " ({:+})", segment.offset)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn composed_matches_chain() {
+    use crate::day_5::{build_key_chain, find_location_for_seed, input_data};
+
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+    let composed = ComposedMap::from_chain(&chain, &maps).unwrap();
+
+    for seed in 0..200 {
+        assert_eq!(
+            composed.get(seed),
+            Some(find_location_for_seed(seed, &chain, &maps).unwrap())
+        );
+    }
+    assert_eq!(composed.min_image(79..93), Some(46));
+
+    let dump = composed.to_string();
+    assert_eq!(dump.lines().count(), composed.segments().len());
+    assert!(dump.starts_with("0..14 -> 22..36 (+22)"), "{dump}");
+}
+
+#[test]
+fn composed_full_almanac() {
+    use crate::day_5::{build_key_chain, input_data};
+
+    let maps = input_data::full::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+    let composed = ComposedMap::from_chain(&chain, &maps).unwrap();
+
+    let almanac = input_data::full::almanac().unwrap();
+    let part_a = almanac
+        .seeds
+        .iter()
+        .map(|s| composed.get(*s).unwrap())
+        .min();
+    assert_eq!(part_a, Some(457535844));
+
+    let part_b = almanac
+        .seed_ranges()
+        .unwrap()
+        .iter()
+        .filter_map(|r| composed.min_image(r.clone()))
+        .min();
+    assert_eq!(part_b, Some(41222968));
+}
+
+#[test]
+fn composing_past_the_top_is_an_error() {
+    let key = ("seed", "location");
+    let shifted = ComposedMap {
+        segments: vec![Segment {
+            source: 0..u64::MAX,
+            offset: 10,
+        }],
+    };
+    assert_eq!(shifted.get(u64::MAX - 20), Some(u64::MAX - 10));
+    assert_eq!(shifted.get(u64::MAX - 5), None);
+    assert_eq!(
+        shifted.min_image(u64::MAX - 20..u64::MAX - 5),
+        Some(u64::MAX - 10)
+    );
+
+    let error = shifted.then(&key, &segments_for(&[])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Composing the seed-to-location map overflows for values in 0..{}",
+            u64::MAX
+        )
+    );
+}
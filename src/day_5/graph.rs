@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use eyre::{bail, eyre};
+
+use crate::day_5::input_data::{Key, Maps};
+
+/// Which categories each map converts into, independent of the maps themselves
+#[derive(Debug, Clone, Default)]
+pub(super) struct CategoryGraph {
+    edges: BTreeMap<&'static str, BTreeSet<&'static str>>,
+}
+
+impl CategoryGraph {
+    pub(super) fn from_maps(maps: &Maps) -> Self {
+        let mut this = Self::default();
+        for (from, to) in maps.keys() {
+            this.edges.entry(from).or_default().insert(to);
+            this.edges.entry(to).or_default();
+        }
+        this
+    }
+
+    pub(super) fn categories(&self) -> Vec<&'static str> {
+        self.edges.keys().copied().collect()
+    }
+
+    pub(super) fn edges(&self) -> Vec<Key> {
+        self.edges
+            .iter()
+            .flat_map(|(from, to)| to.iter().map(|to| (*from, *to)))
+            .collect()
+    }
+
+    /// The chain of maps that converts `from` into `to`. Every category along
+    /// the way must lead to exactly one other category, and the walk may not loop.
+    pub(super) fn path(&self, from: &str, to: &str) -> eyre::Result<Vec<Key>> {
+        let (&(mut current), _) = self
+            .edges
+            .get_key_value(from)
+            .ok_or(eyre!("Unknown category {from}"))?;
+        if !self.edges.contains_key(to) {
+            bail!("Unknown category {to}");
+        }
+
+        let mut chain = vec![];
+        let mut visited = HashSet::from([current]);
+        while current != to {
+            let next = match self.edges[current].iter().collect::<Vec<_>>()[..] {
+                [] => bail!("Exhausted chain at {current} before reaching {to} from {from}"),
+                [next] => *next,
+                ref branches => {
+                    bail!("Ambiguous branch at {current}, it maps to each of {branches:?}")
+                }
+            };
+            if !visited.insert(next) {
+                bail!("Cycle through {next} while looking for {to} from {from}");
+            }
+            chain.push((current, next));
+            current = next;
+        }
+        Ok(chain)
+    }
+}
+
+#[test]
+fn paths_between_categories() {
+    let maps = crate::day_5::input_data::test::get_maps().unwrap();
+    let graph = CategoryGraph::from_maps(&maps);
+
+    assert_eq!(graph.categories().len(), 8);
+    assert_eq!(graph.edges().len(), 7);
+    assert_eq!(
+        graph.path("soil", "humidity").unwrap(),
+        vec![
+            ("soil", "fertilizer"),
+            ("fertilizer", "water"),
+            ("water", "light"),
+            ("light", "temperature"),
+            ("temperature", "humidity"),
+        ]
+    );
+    assert_eq!(graph.path("water", "water").unwrap(), vec![]);
+    assert!(graph.path("location", "seed").is_err());
+    assert!(graph.path("seed", "moon").is_err());
+}
+
+#[test]
+fn cycles_and_branches_are_errors() {
+    let looped = Maps::from([
+        (("seed", "soil"), vec![]),
+        (("soil", "water"), vec![]),
+        (("water", "seed"), vec![]),
+        (("light", "location"), vec![]),
+    ]);
+    let error = CategoryGraph::from_maps(&looped)
+        .path("seed", "location")
+        .unwrap_err();
+    assert!(error.to_string().contains("Cycle through seed"), "{error}");
+
+    let branched = Maps::from([
+        (("seed", "soil"), vec![]),
+        (("seed", "water"), vec![]),
+        (("soil", "location"), vec![]),
+    ]);
+    let error = CategoryGraph::from_maps(&branched)
+        .path("seed", "location")
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("Ambiguous branch at seed"),
+        "{error}"
+    );
+}
@@ -1,44 +1,29 @@
-use crate::day_5::input_data::{Key, Maps};
+use crate::day_5::compose::ComposedMap;
+use crate::day_5::graph::CategoryGraph;
+use crate::day_5::input_data::{parse_almanac, Almanac, Key, Maps, Rule};
+use crate::executor::{Executor, Outcome, Progress, Reduction};
+use crate::parse::Source;
+use eyre::{eyre, WrapErr};
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::num::NonZeroUsize;
+use std::fmt::{Display, Formatter};
 use std::ops::Range;
-use std::thread::{available_parallelism, scope, spawn};
+use std::sync::Mutex;
 
+mod compose;
+mod graph;
 mod input_data;
+mod reverse;
+mod validate;
 
-/// take the set of all maps and find the chain of keys that goes from `location` to `seed`
-fn build_key_chain(maps: &Maps) -> Vec<Key> {
-    let keys = maps.keys();
-
-    let mut chain = vec![];
-    // location link
-    let mut current_target = "location";
-    let mut runs = maps.len() + 1;
-    loop {
-        runs -= 1;
-        if runs == 0 {
-            panic!("Exhausted chain before finding link from seed - location")
-        }
-        let target = keys
-            .clone()
-            .find(|k| k.1 == current_target)
-            .expect("Target could not be found in map");
-        chain.insert(0, *target);
-
-        if target.0 == "seed" {
-            break;
-        } else {
-            current_target = target.0
-        }
-    }
-    return chain;
+/// take the set of all maps and find the chain of keys that goes from `seed` to `location`
+fn build_key_chain(maps: &Maps) -> eyre::Result<Vec<Key>> {
+    CategoryGraph::from_maps(maps).path("seed", "location")
 }
 
 #[test]
 fn test_data_links() {
-    let maps = input_data::test::get_maps();
-    let chain = build_key_chain(&maps);
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
 
     assert_eq!(
         chain,
@@ -54,73 +39,355 @@ fn test_data_links() {
     )
 }
 
-fn find_location_for_seed(seed: usize, lookup_chain: &[Key], maps: &Maps) -> usize {
+/// The rule whose source range holds `value`, found by binary search over the sorted rules
+fn find_rule(value: u64, rules: &[Rule]) -> Option<&Rule> {
+    rules
+        .binary_search_by(|rule| {
+            if rule.source().contains(&value) {
+                Ordering::Equal
+            } else if rule.source().end <= value {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        })
+        .ok()
+        .and_then(|index| rules.get(index))
+}
+
+/// The rules of the map behind `link`
+fn rules_for<'a>(link: &Key, maps: &'a Maps) -> eyre::Result<&'a [Rule]> {
+    maps.get(link)
+        .map(Vec::as_slice)
+        .ok_or(eyre!("No {}-to-{} map in the almanac", link.0, link.1))
+}
+
+/// Sends `value` through a single map, returning the result and the rule that fired
+fn apply_map<'a>(value: u64, link: &Key, maps: &'a Maps) -> eyre::Result<(u64, Option<&'a Rule>)> {
+    let mapping = rules_for(link, maps)?;
+
+    let Some(rule) = find_rule(value, mapping) else {
+        return Ok((value, None));
+    };
+    let floor = value - rule.source().start;
+    let output = floor.checked_add(rule.destination()).ok_or(eyre!(
+        "Rule {} of the {}-to-{} map overflows mapping {value}",
+        rule.index(),
+        link.0,
+        link.1
+    ))?;
+    Ok((output, Some(rule)))
+}
+
+fn find_location_for_seed(seed: u64, lookup_chain: &[Key], maps: &Maps) -> eyre::Result<u64> {
     let mut current = seed;
     for link in lookup_chain {
-        let mapping = maps.get(link).expect("No key in maps");
-        let index = mapping
-            .binary_search_by(|(id, c)| {
-                if c.contains(&current) {
-                    Ordering::Equal
-                } else if c.end <= current {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                }
-            })
-            .ok()
-            .map(|index| mapping.get(index))
-            .flatten();
-
-        if let Some((key, range)) = index {
-            let floor = current - range.start;
-            current = floor + key;
+        current = apply_map(current, link, maps)?.0;
+    }
+    return Ok(current);
+}
+
+/// One map applied to a value on its way through the chain
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct TraceStep {
+    key: Key,
+    input: u64,
+    /// The rule that fired, `None` when the value passed through unchanged
+    rule: Option<Rule>,
+    output: u64,
+}
+
+/// Every category a seed passes through on its way to a location
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Trace {
+    seed: u64,
+    steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    fn location(&self) -> u64 {
+        self.steps.last().map(|s| s.output).unwrap_or(self.seed)
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "seed {}", self.seed)?;
+        for step in &self.steps {
+            write!(f, "\n  -> {} {}", step.key.1, step.output)?;
+            match &step.rule {
+                Some(rule) => write!(
+                    f,
+                    " by rule {} ({} {:?})",
+                    rule.index(),
+                    rule.destination(),
+                    rule.source()
+                )?,
+                None => write!(f, " by identity")?,
+            }
         }
+        Ok(())
     }
-    return current;
+}
+
+fn trace_seed(seed: u64, lookup_chain: &[Key], maps: &Maps) -> eyre::Result<Trace> {
+    let mut current = seed;
+    let mut steps = vec![];
+    for link in lookup_chain {
+        let (output, rule) = apply_map(current, link, maps)?;
+
+        steps.push(TraceStep {
+            key: *link,
+            input: current,
+            rule: rule.cloned(),
+            output,
+        });
+        current = output;
+    }
+    Ok(Trace { seed, steps })
 }
 
 #[test]
 fn find_location_for_seeds() {
-    let maps = input_data::test::get_maps();
-    let chain = build_key_chain(&maps);
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
 
-    let location = find_location_for_seed(79, &chain, &maps);
+    let location = find_location_for_seed(79, &chain, &maps).unwrap();
     assert_eq!(location, 82);
 
-    let location = find_location_for_seed(13, &chain, &maps);
+    let location = find_location_for_seed(13, &chain, &maps).unwrap();
     assert_eq!(location, 35)
 }
 
+#[test]
+fn trace_seeds() {
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+
+    let trace = trace_seed(79, &chain, &maps).unwrap();
+    let values = trace.steps.iter().map(|s| s.output).collect::<Vec<_>>();
+    assert_eq!(values, vec![81, 81, 81, 74, 78, 78, 82]);
+    assert_eq!(
+        trace.location(),
+        find_location_for_seed(79, &chain, &maps).unwrap()
+    );
+    assert_eq!(trace.steps[1].rule, None);
+
+    assert_eq!(
+        trace.to_string().lines().take(3).collect::<Vec<_>>(),
+        vec![
+            "seed 79",
+            "  -> soil 81 by rule 1 (52 50..98)",
+            "  -> fertilizer 81 by identity"
+        ]
+    );
+}
+
+#[test]
+fn overflow_is_an_error() {
+    let key = ("seed", "location");
+    let error = Rule::new(key, 3, u64::MAX - 5, 0, 10).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Rule 3 of the seed-to-location map overflows: destination {} + length 10",
+            u64::MAX - 5
+        )
+    );
+
+    let error = Rule::new(key, 4, 0, u64::MAX - 1, 2).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Rule 4 of the seed-to-location map overflows: source {} + length 2",
+            u64::MAX - 1
+        )
+    );
+
+    // the largest rule that fits maps right up to the top
+    let maps = Maps::from([(key, vec![Rule::new(key, 1, u64::MAX - 10, 0, 10).unwrap()])]);
+    assert_eq!(
+        find_location_for_seed(9, &[key], &maps).unwrap(),
+        u64::MAX - 1
+    );
+}
+
+#[test]
+fn repeated_lookups_cycle() {
+    // the test almanac maps small numbers onto small numbers, so feeding
+    // a location back in as a seed has to loop eventually
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+    let step = |seed: &u64| find_location_for_seed(*seed, &chain, &maps).unwrap();
+
+    let history = crate::cycle::find_with_history(79, step);
+    assert_eq!(crate::cycle::brent(&79, step), history.cycle);
+
+    let naive = (0..500).fold(79, |seed, _| step(&seed));
+    assert_eq!(*history.state_at(500), naive);
+}
+
 #[test]
 fn part_a() {
-    let seeds = input_data::full::SEEDS;
-    let maps = input_data::full::get_maps();
-    let chain = build_key_chain(&maps);
+    let seeds = input_data::full::almanac().unwrap().seeds;
+    let maps = input_data::full::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
 
     let min = seeds
         .iter()
-        .map(|x| find_location_for_seed(*x, &chain, &maps))
+        .map(|x| find_location_for_seed(*x, &chain, &maps).unwrap())
         .min();
 
     assert_eq!(min, Some(457535844));
 }
 
-#[ignore]
+/// Brute force over every seed in every range, minimizing the location.
+/// The first seed that fails to map stops the search and is reported.
+/// `on_progress` hears about each range as it's being searched.
+fn brute_force_lowest_location(
+    seed_ranges: &[Range<u64>],
+    lookup_chain: &[Key],
+    maps: &Maps,
+    mut on_progress: impl FnMut(&Range<u64>, &Progress<u64>),
+) -> eyre::Result<Option<u64>> {
+    let failure = Mutex::new(None);
+    let mut lowest = None;
+    for range in seed_ranges {
+        let outcome = Executor::new()
+            .on_progress(|p: &Progress<u64>| on_progress(range, p))
+            .cancel_when(|_| failure.lock().unwrap().is_some())
+            .run(range.clone(), Reduction::Min, |seed| {
+                find_location_for_seed(seed, lookup_chain, maps).unwrap_or_else(|error| {
+                    let error =
+                        error.wrap_err(format!("Day 5 part 2: seed {seed} has no location"));
+                    failure.lock().unwrap().get_or_insert(error);
+                    u64::MAX
+                })
+            });
+        if let Some(error) = failure.lock().unwrap().take() {
+            return Err(error);
+        }
+        if let Outcome::Complete(Some(value)) = outcome {
+            lowest = Some(lowest.map_or(value, |l: u64| l.min(value)));
+        }
+    }
+    Ok(lowest)
+}
+
 #[test]
-fn part_b() {
-    use rayon::prelude::*;
-    let ranges = input_data::full::SEED_RANGE;
+fn part_b_test() {
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
 
-    let ranges = ranges.iter().cloned().flat_map(|f| f.into_iter());
+    let mut searched = vec![];
+    let value = brute_force_lowest_location(
+        &input_data::test::almanac().unwrap().seed_ranges().unwrap(),
+        &chain,
+        &maps,
+        |range, p| searched.push((range.clone(), p.total)),
+    );
+    assert_eq!(value.unwrap(), Some(46));
+    // every run reports at least once, when it finishes
+    assert_eq!(searched.last(), Some(&(55..68, 13)));
 
-    let maps = input_data::full::get_maps();
-    let chain = build_key_chain(&maps);
+    // a seed that can't be mapped stops the search
+    let key = ("seed", "location");
+    let error =
+        brute_force_lowest_location(&[0..1, 1..7], &[key], &Maps::new(), |_, _| {}).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 5 part 2: seed 0 has no location: No seed-to-location map in the almanac"
+    );
+}
 
-    let value = ranges
-        .par_bridge()
-        .map(|g| find_location_for_seed(g, &chain, &maps))
-        .min();
+/// The almanac and its chain from seed to location, with errors naming the part
+fn read_almanac(input: impl Into<Source<'static>>, part: u8) -> eyre::Result<(Almanac, Vec<Key>)> {
+    let almanac =
+        parse_almanac(input).with_context(|| format!("Day 5 part {part}: invalid almanac"))?;
+    let chain = build_key_chain(&almanac.maps)
+        .with_context(|| format!("Day 5 part {part}: no way from seed to location"))?;
+    Ok((almanac, chain))
+}
+
+/// The lowest location of any seed on the seeds line, `None` without seeds
+fn solve_1(input: impl Into<Source<'static>>) -> eyre::Result<Option<u64>> {
+    let (almanac, chain) = read_almanac(input, 1)?;
+    almanac
+        .seeds
+        .iter()
+        .try_fold(None, |lowest: Option<u64>, seed| {
+            let location = find_location_for_seed(*seed, &chain, &almanac.maps)
+                .with_context(|| format!("Day 5 part 1: seed {seed} has no location"))?;
+            Ok(Some(lowest.map_or(location, |l| l.min(location))))
+        })
+}
+
+/// The lowest location of any seed in the seed ranges, found by collapsing
+/// the chain into a single map instead of trying every seed
+fn solve_2(input: impl Into<Source<'static>>) -> eyre::Result<Option<u64>> {
+    let (almanac, chain) = read_almanac(input, 2)?;
+    let seed_ranges = almanac
+        .seed_ranges()
+        .context("Day 5 part 2: invalid seed ranges")?;
+    let composed = ComposedMap::from_chain(&chain, &almanac.maps)
+        .context("Day 5 part 2: could not compose the maps")?;
+    seed_ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .try_fold(None, |lowest: Option<u64>, range| {
+            let location = composed
+                .min_image(range.clone())
+                .ok_or_else(|| eyre!("Day 5 part 2: seeds {range:?} map past u64::MAX"))?;
+            Ok(Some(lowest.map_or(location, |l| l.min(location))))
+        })
+}
+
+#[test]
+fn solvers() {
+    assert_eq!(solve_1(input_data::test::source()).unwrap(), Some(35));
+    assert_eq!(solve_2(input_data::test::source()).unwrap(), Some(46));
+    assert_eq!(
+        solve_1(input_data::full::source()).unwrap(),
+        Some(457535844)
+    );
+    assert_eq!(solve_2(input_data::full::source()).unwrap(), Some(41222968));
+    assert_eq!(
+        solve_1("seeds:\n\nseed-to-location map:\n1 2 3\n").unwrap(),
+        None
+    );
+
+    let error = solve_1(Source::named("almanac.txt", "seeds: 1 x")).unwrap_err();
+    assert!(
+        format!("{error:#}")
+            .starts_with("Day 5 part 1: invalid almanac: almanac.txt:1:10: Not a valid number x"),
+        "{error:#}"
+    );
+
+    let error = solve_2("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n").unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 5 part 2: no way from seed to location: Unknown category location"
+    );
+
+    let error = solve_2("seeds: 1\n\nseed-to-location map:\n1 2 3\n").unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 5 part 2: invalid seed ranges: Seed ranges come in pairs, got 1 numbers"
+    );
+}
+
+#[ignore]
+#[test]
+fn part_b() {
+    let maps = input_data::full::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+
+    let value = brute_force_lowest_location(
+        &input_data::full::almanac().unwrap().seed_ranges().unwrap(),
+        &chain,
+        &maps,
+        |_, _| {},
+    );
 
-    assert_eq!(value, Some(41222968));
+    assert_eq!(value.unwrap(), Some(41222968));
 }
@@ -0,0 +1,159 @@
+use std::ops::Range;
+
+use eyre::WrapErr;
+
+use crate::day_5::input_data::{Key, Maps, Rule};
+use crate::day_5::rules_for;
+use crate::interval::IntervalSet;
+
+/// Every source value that one map sends into `target`. Values no rule
+/// covers map to themselves, so they count when they fall inside `target`.
+fn preimage(target: &Range<u64>, rules: &[Rule]) -> IntervalSet<u64> {
+    let mapped = rules
+        .iter()
+        .filter_map(|rule| {
+            let destination = rule.destination_range();
+            let (start, end) = (
+                destination.start.max(target.start),
+                destination.end.min(target.end),
+            );
+            // both ends stay within the rule's source range, which can't overflow
+            let back = |v: u64| rule.source().start + (v - rule.destination());
+            (start < end).then(|| back(start)..back(end))
+        })
+        .collect::<IntervalSet<_>>();
+
+    let sources = rules.iter().map(|r| r.source().clone()).collect();
+    let passthrough = IntervalSet::from(target.clone()).difference(&sources);
+
+    mapped.union(&passthrough)
+}
+
+/// Walks the chain backwards and returns every seed that lands somewhere in `locations`
+pub(super) fn find_seeds_for_locations(
+    locations: Range<u64>,
+    lookup_chain: &[Key],
+    maps: &Maps,
+) -> eyre::Result<IntervalSet<u64>> {
+    lookup_chain
+        .iter()
+        .rev()
+        .try_fold(IntervalSet::from(locations), |targets, link| {
+            let rules = rules_for(link, maps)?;
+            Ok(targets.iter().fold(IntervalSet::new(), |seeds, t| {
+                seeds.union(&preimage(&t, rules))
+            }))
+        })
+}
+
+pub(super) fn find_seeds_for_location(
+    location: u64,
+    lookup_chain: &[Key],
+    maps: &Maps,
+) -> eyre::Result<Vec<u64>> {
+    let seeds = find_seeds_for_locations(location..location.saturating_add(1), lookup_chain, maps)?;
+    Ok(seeds.iter().flatten().collect())
+}
+
+/// Finds the lowest location reachable from any of the seed ranges by scanning
+/// locations upwards in blocks, halving a block whenever some seed lands in it.
+pub(super) fn lowest_location_for_seeds(
+    seeds: &[Range<u64>],
+    lookup_chain: &[Key],
+    maps: &Maps,
+) -> eyre::Result<Option<u64>> {
+    let seeds = seeds.iter().cloned().collect::<IntervalSet<_>>();
+    let has_seed = |locations: &Range<u64>| -> eyre::Result<bool> {
+        let found = find_seeds_for_locations(locations.clone(), lookup_chain, maps).wrap_err(
+            format!("Day 5 part 2: looking for seeds in locations {locations:?}"),
+        )?;
+        Ok(!found.intersection(&seeds).is_empty())
+    };
+
+    const BLOCK: u64 = 1 << 20;
+    let mut block = 0..BLOCK;
+    loop {
+        if has_seed(&block)? {
+            break;
+        }
+        let Some(end) = block.end.checked_add(BLOCK) else {
+            return Ok(None);
+        };
+        block = block.end..end;
+    }
+
+    while block.end - block.start > 1 {
+        let middle = block.start + (block.end - block.start) / 2;
+        let lower = block.start..middle;
+        block = if has_seed(&lower)? {
+            lower
+        } else {
+            middle..block.end
+        };
+    }
+    Ok(Some(block.start))
+}
+
+#[test]
+fn seeds_for_locations() {
+    use crate::day_5::{build_key_chain, find_location_for_seed, input_data};
+
+    let maps = input_data::test::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+
+    assert!(find_seeds_for_location(82, &chain, &maps)
+        .unwrap()
+        .contains(&79));
+    assert!(find_seeds_for_location(35, &chain, &maps)
+        .unwrap()
+        .contains(&13));
+
+    // every seed found has to map forward into the queried locations
+    for seed in find_seeds_for_locations(40..60, &chain, &maps)
+        .unwrap()
+        .iter()
+        .flatten()
+    {
+        assert!((40..60).contains(&find_location_for_seed(seed, &chain, &maps).unwrap()));
+    }
+    // and every seed mapping into them has to be found
+    let found = find_seeds_for_locations(40..60, &chain, &maps).unwrap();
+    for seed in 0..200 {
+        if (40..60).contains(&find_location_for_seed(seed, &chain, &maps).unwrap()) {
+            assert!(found.contains(seed), "missing {seed}");
+        }
+    }
+
+    let lowest = lowest_location_for_seeds(
+        &input_data::test::almanac().unwrap().seed_ranges().unwrap(),
+        &chain,
+        &maps,
+    );
+    assert_eq!(lowest.unwrap(), Some(46));
+
+    let error = lowest_location_for_seeds(
+        &input_data::test::almanac().unwrap().seed_ranges().unwrap(),
+        &[("seed", "nowhere")],
+        &maps,
+    )
+    .unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 5 part 2: looking for seeds in locations 0..1048576: No seed-to-nowhere map in the almanac"
+    );
+}
+
+#[test]
+fn part_b_from_locations() {
+    use crate::day_5::{build_key_chain, input_data};
+
+    let maps = input_data::full::get_maps().unwrap();
+    let chain = build_key_chain(&maps).unwrap();
+
+    let lowest = lowest_location_for_seeds(
+        &input_data::full::almanac().unwrap().seed_ranges().unwrap(),
+        &chain,
+        &maps,
+    );
+    assert_eq!(lowest.unwrap(), Some(41222968));
+}
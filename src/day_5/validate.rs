@@ -0,0 +1,154 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use eyre::bail;
+
+use crate::day_5::input_data::{Key, Maps, Rule};
+use crate::interval::IntervalSet;
+
+/// Everything suspicious about a single map. Rules are named by their
+/// position in the almanac, not their position after sorting.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct MapReport {
+    pub key: Key,
+    /// Pairs of rules whose source ranges overlap, so a value could map two ways
+    pub overlapping_sources: Vec<(usize, usize)>,
+    /// Pairs of rules mapping onto the same values, so the map isn't injective
+    pub overlapping_destinations: Vec<(usize, usize)>,
+    /// Source values below the last rule that no rule covers and pass through unchanged
+    pub gaps: Vec<Range<u64>>,
+}
+
+impl MapReport {
+    /// Gaps are legal in an almanac, overlapping sources are not
+    pub(super) fn is_corrupt(&self) -> bool {
+        !self.overlapping_sources.is_empty()
+    }
+}
+
+impl Display for MapReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-to-{} map:", self.key.0, self.key.1)?;
+        for (a, b) in &self.overlapping_sources {
+            write!(f, "\n  rules {a} and {b} have overlapping sources")?;
+        }
+        for (a, b) in &self.overlapping_destinations {
+            write!(f, "\n  rules {a} and {b} have overlapping destinations")?;
+        }
+        for gap in &self.gaps {
+            write!(f, "\n  {gap:?} is not covered")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every pair of overlapping ranges, found with a sweep over the ranges sorted by start
+fn overlapping_pairs(rules: &[Rule], range: impl Fn(&Rule) -> Range<u64>) -> Vec<(usize, usize)> {
+    let mut sorted = rules.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|rule| range(rule).start);
+
+    let mut pairs = vec![];
+    let mut open: Vec<&Rule> = vec![];
+    for rule in sorted {
+        let current = range(rule);
+        open.retain(|other| range(other).end > current.start);
+        for other in &open {
+            pairs.push((
+                other.index().min(rule.index()),
+                other.index().max(rule.index()),
+            ));
+        }
+        if !current.is_empty() {
+            open.push(rule);
+        }
+    }
+    pairs.sort();
+    pairs
+}
+
+fn gaps(rules: &[Rule]) -> Vec<Range<u64>> {
+    let sources = rules
+        .iter()
+        .map(|r| r.source().clone())
+        .collect::<IntervalSet<_>>();
+    let last = sources.iter().last().map(|r| r.end).unwrap_or_default();
+    IntervalSet::from(0..last)
+        .difference(&sources)
+        .iter()
+        .collect()
+}
+
+/// Reports on every map in the almanac, ordered by key
+pub(super) fn validate_maps(maps: &Maps) -> Vec<MapReport> {
+    let mut reports = maps
+        .iter()
+        .map(|(key, rules)| MapReport {
+            key: *key,
+            overlapping_sources: overlapping_pairs(rules, |r| r.source().clone()),
+            overlapping_destinations: overlapping_pairs(rules, |r| r.destination_range()),
+            gaps: gaps(rules),
+        })
+        .collect::<Vec<_>>();
+    reports.sort_by_key(|r| r.key);
+    reports
+}
+
+/// Fails when any map has rules fighting over the same source values
+pub(super) fn check_maps(maps: &Maps) -> eyre::Result<()> {
+    let corrupt = validate_maps(maps)
+        .into_iter()
+        .filter(MapReport::is_corrupt)
+        .map(|r| r.to_string())
+        .collect::<Vec<_>>();
+    if !corrupt.is_empty() {
+        bail!("Almanac has overlapping maps:\n{}", corrupt.join("\n"));
+    }
+    Ok(())
+}
+
+#[test]
+fn almanacs_are_consistent() {
+    use crate::day_5::input_data;
+
+    check_maps(&input_data::test::get_maps().unwrap()).unwrap();
+    check_maps(&input_data::full::get_maps().unwrap()).unwrap();
+
+    let reports = validate_maps(&input_data::test::get_maps().unwrap());
+    let seed_to_soil = reports.iter().find(|r| r.key == ("seed", "soil")).unwrap();
+    assert_eq!(seed_to_soil.gaps, vec![0..50]);
+    assert!(seed_to_soil.overlapping_destinations.is_empty());
+}
+
+#[test]
+fn catches_corrupted_map() {
+    let rule = |index, destination, source: Range<u64>| {
+        Rule::new(
+            ("seed", "soil"),
+            index,
+            destination,
+            source.start,
+            source.end - source.start,
+        )
+        .unwrap()
+    };
+    let maps = Maps::from([(
+        ("seed", "soil"),
+        vec![
+            rule(2, 100, 0..10),
+            rule(0, 0, 5..20),
+            rule(1, 105, 30..40),
+            rule(3, 200, 35..36),
+        ],
+    )]);
+
+    let report = &validate_maps(&maps)[0];
+    assert_eq!(report.overlapping_sources, vec![(0, 2), (1, 3)]);
+    assert_eq!(report.overlapping_destinations, vec![(1, 2)]);
+    assert_eq!(report.gaps, vec![20..30]);
+
+    let error = check_maps(&maps).unwrap_err().to_string();
+    assert!(
+        error.contains("rules 0 and 2 have overlapping sources"),
+        "{error}"
+    );
+}
@@ -1,48 +1,38 @@
-// Time:        47     98     66     98
-// Distance:   400   1213   1011   1540
+use eyre::bail;
 
-use std::num::NonZeroUsize;
+use crate::day_6::kerning::{parse_kerned_race, parse_races, KernedRace};
+use crate::parse::Source;
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Race {
     pub time_ms: usize,
     pub distance_mm: usize,
 }
 
-pub static FINAL: Race = Race {
-    time_ms: 47_98_66_98,
-    distance_mm: 400_1213_1011_1540,
+pub static INPUT: Source = Source {
+    file: Some("day_6/input.txt"),
+    text: include_str!("./input.txt"),
 };
 
-pub static FULL: &'static [Race] = &[
-    Race {
-        time_ms: 47,
-        distance_mm: 400,
-    },
-    Race {
-        time_ms: 98,
-        distance_mm: 1213,
-    },
-    Race {
-        time_ms: 66,
-        distance_mm: 1011,
-    },
-    Race {
-        time_ms: 98,
-        distance_mm: 1540,
-    },
-];
+pub static TEST_INPUT: Source = Source {
+    file: Some("day_6/test.txt"),
+    text: include_str!("./test.txt"),
+};
+
+pub fn full_races() -> eyre::Result<Vec<Race>> {
+    parse_races(INPUT)
+}
 
-pub static TEST: &'static [Race] = &[
-    Race {
-        time_ms: 7,
-        distance_mm: 9,
-    },
-    Race {
-        time_ms: 15,
-        distance_mm: 40,
-    },
-    Race {
-        time_ms: 30,
-        distance_mm: 200,
-    },
-];
+pub fn test_races() -> eyre::Result<Vec<Race>> {
+    parse_races(TEST_INPUT)
+}
+
+/// The part 2 race of the puzzle input, which fits in a `usize`
+pub fn kerned_race() -> eyre::Result<Race> {
+    match parse_kerned_race(INPUT)? {
+        KernedRace::Small(race) => Ok(race),
+        KernedRace::Big(race) => {
+            bail!("Day 6 part 2: kerned race {race:?} does not fit in a usize")
+        }
+    }
+}
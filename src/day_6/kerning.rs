@@ -0,0 +1,227 @@
+use eyre::{eyre, Context};
+use num_bigint::BigUint;
+
+use crate::day_6::input::Race;
+use crate::parse::{Line, Source};
+
+/// A race whose numbers don't fit in a `usize`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct BigRace {
+    pub time_ms: BigUint,
+    pub distance_mm: BigUint,
+}
+
+impl BigRace {
+    fn distance_for_hold(&self, hold: &BigUint) -> BigUint {
+        hold * (&self.time_ms - hold)
+    }
+
+    /// The same parabola as the small races, solved with an exact integer square root
+    pub(super) fn ways_to_win(&self) -> BigUint {
+        let (time, record) = (&self.time_ms, &self.distance_mm);
+        let half = time / 2u32;
+        let discriminant = time * time;
+        let four_records = record * 4u32;
+        if discriminant <= four_records {
+            return BigUint::ZERO;
+        }
+
+        let root = (discriminant - four_records).sqrt();
+        let mut first = (time - &root) / 2u32;
+        while first <= half && self.distance_for_hold(&first) <= *record {
+            first += 1u32;
+        }
+        while first > BigUint::ZERO && self.distance_for_hold(&(&first - 1u32)) > *record {
+            first -= 1u32;
+        }
+
+        if first > half {
+            BigUint::ZERO
+        } else {
+            time - &first * 2u32 + 1u32
+        }
+    }
+}
+
+/// The single race from part B, kept as a plain `Race` whenever it fits
+#[derive(Debug)]
+pub(super) enum KernedRace {
+    Small(Race),
+    Big(BigRace),
+}
+
+impl From<&Race> for BigRace {
+    fn from(race: &Race) -> Self {
+        Self {
+            time_ms: race.time_ms.into(),
+            distance_mm: race.distance_mm.into(),
+        }
+    }
+}
+
+/// The float solver squares the time, so it's only exact while that fits in
+/// the 53 bit mantissa of an `f64`
+const F64_EXACT: u128 = 1 << 53;
+
+impl KernedRace {
+    pub(super) fn ways_to_win(&self) -> BigUint {
+        match self {
+            KernedRace::Small(race) if (race.time_ms as u128).pow(2) < F64_EXACT => {
+                BigUint::from(super::compute_max_ways_to_win(race))
+            }
+            KernedRace::Small(race) => BigRace::from(race).ways_to_win(),
+            KernedRace::Big(race) => race.ways_to_win(),
+        }
+    }
+}
+
+/// Splits the `Time:` and `Distance:` lines into their number columns
+fn parse_columns(source: Source) -> eyre::Result<(Vec<Line>, Vec<Line>)> {
+    let mut lines = source.lines().filter(|line| !line.is_blank());
+    let mut columns = |label: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| source.end().error(format!("Missing {label} line")))?;
+        let values = line.field(label)?.words().collect::<Vec<_>>();
+        if let Some(bad) = values
+            .iter()
+            .find(|v| !v.text.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(bad.error(format!("Not a valid number {} in {label}", bad.text)));
+        }
+        Ok((line, values))
+    };
+
+    let ((_, times), (line, distances)) = (columns("Time")?, columns("Distance")?);
+    if times.len() != distances.len() {
+        return Err(line.error(format!(
+            "Got {} times but {} distances",
+            times.len(),
+            distances.len()
+        )));
+    }
+    Ok((times, distances))
+}
+
+pub(super) fn parse_races<'a>(input: impl Into<Source<'a>>) -> eyre::Result<Vec<Race>> {
+    let (times, distances) =
+        parse_columns(input.into()).context("Day 6 part 1: invalid race sheet")?;
+    times
+        .into_iter()
+        .zip(distances)
+        .enumerate()
+        .map(|(index, (time, distance))| {
+            let race = || format!("Day 6 part 1: race {}", index + 1);
+            Ok(Race {
+                time_ms: time.number().with_context(race)?,
+                distance_mm: distance.number().with_context(race)?,
+            })
+        })
+        .collect()
+}
+
+/// Reads the columns as one race with the spaces kerned away. Falls back to
+/// big integers when either number is too long for a `usize`.
+pub(super) fn parse_kerned_race<'a>(input: impl Into<Source<'a>>) -> eyre::Result<KernedRace> {
+    let (times, distances) =
+        parse_columns(input.into()).context("Day 6 part 2: invalid race sheet")?;
+    let concat = |column: Vec<Line>| column.iter().map(|l| l.text).collect::<String>();
+    let (time, distance) = (concat(times), concat(distances));
+
+    if let (Ok(time_ms), Ok(distance_mm)) = (time.parse(), distance.parse()) {
+        return Ok(KernedRace::Small(Race {
+            time_ms,
+            distance_mm,
+        }));
+    }
+
+    let big = |digits: &str| {
+        BigUint::parse_bytes(digits.as_bytes(), 10).ok_or(eyre!("Not a valid number {digits}"))
+    };
+    Ok(KernedRace::Big(BigRace {
+        time_ms: big(&time)?,
+        distance_mm: big(&distance)?,
+    }))
+}
+
+#[test]
+fn kerned_races() {
+    let input = r"
+    Time:      7  15   30
+    Distance:  9  40  200
+    ";
+
+    assert_eq!(parse_races(input).unwrap().len(), 3);
+    let race = parse_kerned_race(input).unwrap();
+    assert!(matches!(race, KernedRace::Small(_)));
+    assert_eq!(race.ways_to_win(), BigUint::from(71503u32));
+
+    let full = r"
+    Time:        47     98     66     98
+    Distance:   400   1213   1011   1540
+    ";
+    assert_eq!(
+        parse_kerned_race(full).unwrap().ways_to_win(),
+        BigUint::from(26499773u32)
+    );
+
+    let error = parse_races("Time: 7 99999999999999999999\nDistance: 9 40").unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Day 6 part 1: race 2: line 1, column 9: Not a valid number 99999999999999999999: number too large to fit in target type
+  |
+1 | Time: 7 99999999999999999999
+  |         ^^^^^^^^^^^^^^^^^^^^"
+    );
+
+    let error = parse_kerned_race(Source::named("races.txt", "Time: 7 15\n")).unwrap_err();
+    assert_eq!(
+        error.root_cause().to_string(),
+        "races.txt:2:1: Missing Distance line\n  |\n2 | \n  | ^"
+    );
+}
+
+#[test]
+fn big_races() {
+    let input = r"
+    Time:        47986698  47986698  47986698
+    Distance:   400121310111540  400121310111540  123
+    ";
+
+    let race = parse_kerned_race(input).unwrap();
+    let KernedRace::Big(big) = &race else {
+        panic!("expected a big race, got {race:?}");
+    };
+    assert_eq!(big.time_ms.to_string(), "479866984798669847986698");
+    assert_eq!(race.ways_to_win().to_string(), "479866984798668180352447");
+
+    // the big solver agrees with the float one wherever both work
+    for race in &crate::day_6::input::full_races().unwrap() {
+        let big = BigRace::from(race);
+        assert_eq!(
+            big.ways_to_win(),
+            BigUint::from(super::compute_max_ways_to_win(race))
+        );
+    }
+}
+
+#[test]
+fn small_races_too_big_for_floats() {
+    let ways = |input: &str| {
+        let race = parse_kerned_race(input).unwrap();
+        assert!(matches!(race, KernedRace::Small(_)));
+        race.ways_to_win().to_string()
+    };
+
+    assert_eq!(
+        ways("Time: 1000000000000000000\nDistance: 10000000000000000000"),
+        "999999999999999979"
+    );
+    assert_eq!(
+        ways("Time: 18000000000000000123\nDistance: 17000000000000000001"),
+        "18000000000000000122"
+    );
+    // right at the limit of the float solver, and just past it
+    assert_eq!(ways("Time: 94906265\nDistance: 1000"), "94906264");
+    assert_eq!(ways("Time: 94906266\nDistance: 1000"), "94906265");
+}
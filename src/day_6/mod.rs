@@ -1,6 +1,14 @@
-use crate::day_6::input::{Race, FINAL, FULL, TEST};
+use crate::day_6::input::Race;
+#[cfg(test)]
+use crate::day_6::input::{full_races, kerned_race, test_races, INPUT, TEST_INPUT};
+use crate::day_6::kerning::{parse_kerned_race, parse_races};
+use crate::parse::Source;
+use num_bigint::BigUint;
+use std::ops::Range;
 
 mod input;
+mod kerning;
+mod model;
 
 fn compute_max_ways_to_win(race: &Race) -> usize {
     // observation is that this is a quadratic inequality with the form
@@ -23,27 +31,161 @@ fn compute_max_ways_to_win(race: &Race) -> usize {
     return range.len();
 }
 
+/// Product of the ways to win every race on the sheet
+fn solve_1<'a>(input: impl Into<Source<'a>>) -> eyre::Result<usize> {
+    Ok(parse_races(input)?
+        .iter()
+        .map(compute_max_ways_to_win)
+        .product())
+}
+
+/// Ways to win the single race the sheet reads as once the spaces are kerned away
+fn solve_2<'a>(input: impl Into<Source<'a>>) -> eyre::Result<BigUint> {
+    Ok(parse_kerned_race(input)?.ways_to_win())
+}
+
+/// Everything about a race beyond the number of ways to win it
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RaceOutcome {
+    /// Every hold time that beats the record, empty when none do
+    winning_holds: Range<usize>,
+    optimal_hold: usize,
+    optimal_distance: u128,
+}
+
+/// In `u128`, where any product of two `usize` halves of the race fits
+fn distance_for_hold(race: &Race, hold: usize) -> u128 {
+    hold as u128 * (race.time_ms - hold) as u128
+}
+
+fn analyze_race(race: &Race) -> RaceOutcome {
+    // same parabola as above, but the float root is only used as a starting
+    // point and nudged until it's exactly the first winning integer
+    let (duration, distance) = (race.time_ms as f64, race.distance_mm as f64);
+    let optimal_hold = race.time_ms / 2;
+    let estimate = ((duration - (duration.powi(2) - (4.0 * distance)).max(0.0).sqrt()) / 2.0)
+        .floor()
+        .clamp(0.0, optimal_hold as f64) as usize;
+
+    let record = race.distance_mm as u128;
+    let mut first = estimate;
+    while first > 0 && distance_for_hold(race, first - 1) > record {
+        first -= 1;
+    }
+    while first <= optimal_hold && distance_for_hold(race, first) <= record {
+        first += 1;
+    }
+
+    let winning_holds = if first > optimal_hold {
+        0..0
+    } else {
+        // the parabola is symmetric around t / 2
+        first..(race.time_ms - first + 1)
+    };
+
+    RaceOutcome {
+        winning_holds,
+        optimal_hold,
+        optimal_distance: distance_for_hold(race, optimal_hold),
+    }
+}
+
+/// How far past the record every winning hold time goes
+fn winning_margins(race: &Race) -> impl Iterator<Item = (usize, u128)> + '_ {
+    analyze_race(race).winning_holds.map(move |hold| {
+        (
+            hold,
+            distance_for_hold(race, hold) - race.distance_mm as u128,
+        )
+    })
+}
+
 #[test]
 fn base_cases() {
-    assert_eq!(
-        288,
-        TEST.iter()
-            .map(|t| compute_max_ways_to_win(t))
-            .product::<usize>()
-    )
+    assert_eq!(288, solve_1(TEST_INPUT).unwrap());
+    assert_eq!(BigUint::from(71503u32), solve_2(TEST_INPUT).unwrap());
 }
 
 #[test]
 fn part_a() {
-    assert_eq!(
-        1660968,
-        FULL.iter()
-            .map(|t| compute_max_ways_to_win(t))
-            .product::<usize>()
-    )
+    assert_eq!(1660968, solve_1(INPUT).unwrap())
 }
 
 #[test]
 fn part_b() {
-    assert_eq!(26499773, compute_max_ways_to_win(&FINAL))
+    assert_eq!(BigUint::from(26499773u32), solve_2(INPUT).unwrap());
+    assert_eq!(26499773, compute_max_ways_to_win(&kerned_race().unwrap()))
+}
+
+#[test]
+fn bad_sheets() {
+    let error = solve_1("Time: 7 x\nDistance: 9 40").unwrap_err();
+    assert!(
+        format!("{error:#}").starts_with("Day 6 part 1: invalid race sheet: "),
+        "{error:#}"
+    );
+    let error = solve_2("Time: 7\n").unwrap_err();
+    assert!(
+        format!("{error:#}").starts_with("Day 6 part 2: invalid race sheet: "),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn race_outcomes() {
+    let test = test_races().unwrap();
+    let outcome = analyze_race(&test[0]);
+    assert_eq!(
+        outcome,
+        RaceOutcome {
+            winning_holds: 2..6,
+            optimal_hold: 3,
+            optimal_distance: 12,
+        }
+    );
+    assert_eq!(
+        winning_margins(&test[0]).collect::<Vec<_>>(),
+        vec![(2, 1), (3, 3), (4, 3), (5, 1)]
+    );
+
+    // 10 and 20 both travel exactly 200mm, which only ties the record
+    assert_eq!(analyze_race(&test[2]).winning_holds, 11..20);
+
+    let unwinnable = Race {
+        time_ms: 4,
+        distance_mm: 4,
+    };
+    assert_eq!(analyze_race(&unwinnable).winning_holds, 0..0);
+
+    let full = full_races().unwrap();
+    let kerned = kerned_race().unwrap();
+    for race in test.iter().chain(&full).chain([&kerned]) {
+        assert_eq!(
+            analyze_race(race).winning_holds.len(),
+            compute_max_ways_to_win(race)
+        );
+    }
+}
+
+#[test]
+fn long_races() {
+    // the best distance is 2.5e19, more than fits in a u64
+    let race = Race {
+        time_ms: 10_000_000_000,
+        distance_mm: 1,
+    };
+    let outcome = analyze_race(&race);
+    assert_eq!(outcome.winning_holds, 1..10_000_000_000);
+    assert_eq!(outcome.optimal_distance, 25_000_000_000_000_000_000);
+    assert_eq!(winning_margins(&race).next(), Some((1, 10_000_000_000 - 2)));
+
+    let race = Race {
+        time_ms: usize::MAX,
+        distance_mm: usize::MAX,
+    };
+    let exact = kerning::BigRace::from(&race).ways_to_win();
+    assert_eq!(
+        BigUint::from(analyze_race(&race).winning_holds.len()),
+        exact
+    );
 }
@@ -0,0 +1,229 @@
+use std::ops::Range;
+
+use crate::interval::IntervalSet;
+
+/// How a boat moves. Holding the button for `hold` ms charges the boat to
+/// `starting_speed + charge_rate * min(hold, charge_cap)` mm/ms. Once released it
+/// covers its current speed every ms, then loses `drag` mm/ms of speed, until
+/// either it stops or the race is over.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) struct BoatModel {
+    pub charge_rate: u64,
+    /// Holding longer than this wastes time without adding speed
+    pub charge_cap: Option<u64>,
+    pub drag: u64,
+    pub starting_speed: u64,
+}
+
+impl Default for BoatModel {
+    /// The boats from the puzzle, where `d = w(t - w)`
+    fn default() -> Self {
+        Self {
+            charge_rate: 1,
+            charge_cap: None,
+            drag: 0,
+            starting_speed: 0,
+        }
+    }
+}
+
+/// The first value in `range` where `predicate` holds, for a predicate that
+/// is false up to some point and true from there on. `range.end` when it never holds.
+fn first_where(range: Range<u64>, predicate: impl Fn(u64) -> bool) -> u64 {
+    let (mut low, mut high) = (range.start, range.end);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    low
+}
+
+impl BoatModel {
+    fn speed(&self, hold: u64) -> u128 {
+        let charged = self.charge_cap.map_or(hold, |cap| hold.min(cap));
+        self.starting_speed as u128 + self.charge_rate as u128 * charged as u128
+    }
+
+    /// Milliseconds the boat spends moving at a positive speed, if nothing stopped it
+    fn moving_steps(&self, speed: u128) -> Option<u128> {
+        (self.drag > 0).then(|| speed.div_ceil(self.drag as u128))
+    }
+
+    pub(super) fn distance(&self, time: u64, hold: u64) -> u128 {
+        let (speed, remaining) = (self.speed(hold), (time - hold) as u128);
+        let steps = self
+            .moving_steps(speed)
+            .map_or(remaining, |moving| moving.min(remaining));
+        // speed + (speed - drag) + (speed - 2 drag) + ... over `steps` ms
+        steps * speed - self.drag as u128 * steps * steps.saturating_sub(1) / 2
+    }
+
+    /// Every hold time that goes further than `record` in a race lasting `time` ms
+    pub(super) fn winning_holds(&self, time: u64, record: u64) -> IntervalSet<u64> {
+        let wins = |hold: u64| self.distance(time, hold) > record as u128;
+        let charge_end = self.charge_cap.map_or(time, |cap| cap.min(time));
+        let mut output = IntervalSet::new();
+
+        // while charging, the boat stalls before the race ends for short holds.
+        // Stalled distance only grows with the hold so its winners are a suffix
+        let stalled = |hold: u64| {
+            self.moving_steps(self.speed(hold))
+                .is_some_and(|moving| moving < (time - hold) as u128)
+        };
+        let quadratic_start = first_where(0..charge_end + 1, |hold| !stalled(hold));
+        let first_stalled_win = first_where(0..quadratic_start, wins);
+        output.insert(first_stalled_win..quadratic_start);
+
+        // charging and never stalling, the distance is a concave quadratic in the hold
+        if quadratic_start <= charge_end {
+            output.insert(self.quadratic_winners(time, record, quadratic_start..charge_end + 1));
+        }
+
+        // past the cap the speed is fixed and every extra ms held loses distance,
+        // so the winners are a prefix
+        if charge_end < time {
+            let first_loss = first_where(charge_end + 1..time + 1, |hold| !wins(hold));
+            output.insert(charge_end + 1..first_loss);
+        }
+
+        output
+    }
+
+    /// Solves `(t - w)(s + r w) - k (t - w)(t - w - 1) / 2 > d` for integer `w` in `region`.
+    /// The float roots are only a starting point, the bounds are then moved
+    /// with exact integer distances until they're correct.
+    fn quadratic_winners(&self, time: u64, record: u64, region: Range<u64>) -> Range<u64> {
+        let wins = |hold: u64| self.distance(time, hold) > record as u128;
+        let (t, s, r, k, d) = (
+            time as f64,
+            self.starting_speed as f64,
+            self.charge_rate as f64,
+            self.drag as f64,
+            record as f64,
+        );
+        // twice the inequality, as a w^2 + b w + c > 0
+        let a = -2.0 * r - k;
+        let b = 2.0 * (t * r - s) - k * (1.0 - 2.0 * t);
+        let c = 2.0 * t * s - k * (t * t - t) - 2.0 * d;
+
+        let (low, high) = (region.start, region.end - 1);
+        let clamp = |x: f64| x.max(low as f64).min(high as f64) as u64;
+        if a == 0.0 {
+            // no charge and no drag, the distance only shrinks with the hold
+            return low..first_where(region, |hold| !wins(hold));
+        }
+
+        let vertex = clamp(-b / (2.0 * a));
+        let best = [vertex.saturating_sub(1), vertex, vertex + 1]
+            .into_iter()
+            .map(|hold| hold.clamp(low, high))
+            .max_by_key(|hold| self.distance(time, *hold))
+            .unwrap();
+        if !wins(best) {
+            return 0..0;
+        }
+
+        let root = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        let mut first = clamp(((-b + root) / (2.0 * a)).ceil()).min(best);
+        while first > low && wins(first - 1) {
+            first -= 1;
+        }
+        while !wins(first) {
+            first += 1;
+        }
+
+        let mut last = clamp(((-b - root) / (2.0 * a)).floor()).max(best);
+        while last < high && wins(last + 1) {
+            last += 1;
+        }
+        while !wins(last) {
+            last -= 1;
+        }
+
+        first..last + 1
+    }
+
+    pub(super) fn ways_to_win(&self, time: u64, record: u64) -> u64 {
+        self.winning_holds(time, record)
+            .iter()
+            .map(|r| r.end - r.start)
+            .sum()
+    }
+}
+
+#[test]
+fn matches_brute_force() {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = move |bound: u64| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % bound
+    };
+
+    for _ in 0..2000 {
+        let model = BoatModel {
+            charge_rate: next(4),
+            charge_cap: (next(2) == 0).then(|| next(40)),
+            drag: next(4),
+            starting_speed: next(6),
+        };
+        let time = next(60);
+        let record = next(400);
+
+        let brute: IntervalSet<u64> = (0..=time)
+            .filter(|hold| model.distance(time, *hold) > record as u128)
+            .map(|hold| hold..hold + 1)
+            .collect();
+        assert_eq!(
+            model.winning_holds(time, record),
+            brute,
+            "{model:?} time {time} record {record}"
+        );
+    }
+}
+
+#[test]
+fn default_model_is_the_puzzle() {
+    use crate::day_6::input::{full_races, kerned_race};
+
+    let model = BoatModel::default();
+    assert_eq!(model.distance(7, 3), 12);
+
+    let part_a: u64 = full_races()
+        .unwrap()
+        .iter()
+        .map(|r| model.ways_to_win(r.time_ms as u64, r.distance_mm as u64))
+        .product();
+    assert_eq!(part_a, 1660968);
+    let race = kerned_race().unwrap();
+    assert_eq!(
+        model.ways_to_win(race.time_ms as u64, race.distance_mm as u64),
+        26499773
+    );
+}
+
+#[test]
+fn capped_and_dragged_boats() {
+    // capped at 2: holds of 2..=5 all reach 2 mm/ms, then lose time
+    let capped = BoatModel {
+        charge_cap: Some(2),
+        ..BoatModel::default()
+    };
+    assert_eq!(
+        (0..=7).map(|w| capped.distance(7, w)).collect::<Vec<_>>(),
+        vec![0, 6, 10, 8, 6, 4, 2, 0]
+    );
+
+    // dragged: 5 mm/ms loses 2 each ms, covering 5 + 3 + 1 before stopping
+    let dragged = BoatModel {
+        drag: 2,
+        ..BoatModel::default()
+    };
+    assert_eq!(dragged.distance(20, 5), 9);
+    assert_eq!(dragged.distance(7, 5), 5 + 3);
+}
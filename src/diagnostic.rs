@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// A problem at a known spot of some input. It prints like a compiler error
+/// with the offending text underlined, and since it's an `Error` it can sit
+/// anywhere in an `eyre` report:
+///
+/// ```text
+/// day_5/test.txt:1:10: Not a valid number x: invalid digit found in string
+///   |
+/// 1 | seeds: 1 x
+///   |          ^
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Diagnostic {
+    pub message: String,
+    pub file: Option<String>,
+    /// 1-based line number
+    pub line: usize,
+    /// Byte range of the offending text within `source`
+    pub span: Range<usize>,
+    /// The whole input line the span points into
+    pub source: String,
+}
+
+impl Diagnostic {
+    /// 1-based column the span starts at, counted in characters
+    pub(crate) fn column(&self) -> usize {
+        self.source[..self.span.start].chars().count() + 1
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{file}:{}:{}: ", self.line, self.column())?,
+            None => write!(f, "line {}, column {}: ", self.line, self.column())?,
+        }
+        writeln!(f, "{}", self.message)?;
+
+        // keep tabs so the carets line up with the text above them
+        let padding = self.source[..self.span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let carets = self.source[self.span.clone()].chars().count().max(1);
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        write!(f, "{gutter} | {padding}{}", "^".repeat(carets))
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+#[test]
+fn underlines_the_span() {
+    let diagnostic = Diagnostic {
+        message: "Not a valid number x".into(),
+        file: Some("day_5/test.txt".into()),
+        line: 12,
+        span: 10..12,
+        source: "seeds: 1 \tx7 3".into(),
+    };
+    assert_eq!(diagnostic.column(), 11);
+    assert_eq!(
+        diagnostic.to_string(),
+        "day_5/test.txt:12:11: Not a valid number x\n   |\n12 | seeds: 1 \tx7 3\n   |          \t^^"
+    );
+
+    let at_end = Diagnostic {
+        file: None,
+        span: 3..3,
+        source: "abc".into(),
+        ..diagnostic
+    };
+    assert!(at_end
+        .to_string()
+        .starts_with("line 12, column 4: Not a valid number x\n"));
+    assert!(at_end.to_string().ends_with("\n   |    ^"));
+
+    // wrapped in context, the report still leads back to the diagnostic
+    let report = eyre::Report::new(at_end.clone()).wrap_err("Day 5: invalid almanac");
+    assert_eq!(
+        report.root_cause().downcast_ref::<Diagnostic>(),
+        Some(&at_end)
+    );
+    assert!(format!("{report:?}").contains("12 | abc"));
+}
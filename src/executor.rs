@@ -0,0 +1,331 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::panic::resume_unwind;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{available_parallelism, scope, sleep};
+use std::time::{Duration, Instant};
+
+/// How the values produced for each number in the range are combined
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Reduction {
+    Min,
+    Max,
+    Sum,
+}
+
+impl Reduction {
+    /// Folds `b` into the running value `a`, `None` when a sum overflows
+    fn combine<T: Ord + CheckedAdd>(self, a: Option<T>, b: T) -> Option<T> {
+        match (self, a) {
+            (_, None) => Some(b),
+            (Reduction::Min, Some(a)) => Some(a.min(b)),
+            (Reduction::Max, Some(a)) => Some(a.max(b)),
+            (Reduction::Sum, Some(a)) => a.checked_add(b),
+        }
+    }
+}
+
+/// Addition that reports overflow, so a [`Reduction::Sum`] can stop instead of panicking
+pub(crate) trait CheckedAdd: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! checked_add {
+    ($($t:ty),*) => {$(
+        impl CheckedAdd for $t {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        }
+    )*};
+}
+
+checked_add!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A snapshot handed to the progress callback and the cancellation predicate
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Progress<T> {
+    pub processed: u64,
+    pub total: u64,
+    pub elapsed: Duration,
+    /// The reduction over every chunk finished so far
+    pub current: Option<T>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Outcome<T> {
+    Complete(Option<T>),
+    /// Stopped early after `processed` numbers were reduced into `partial`.
+    /// Chunks start in ascending order but may finish out of order.
+    Cancelled {
+        partial: Option<T>,
+        processed: u64,
+    },
+    /// A [`Reduction::Sum`] ran past the largest `T`, which stopped the run
+    /// after `processed` numbers
+    Overflowed {
+        processed: u64,
+    },
+}
+
+type ProgressCallback<'a, T> = Box<dyn FnMut(&Progress<T>) + 'a>;
+type CancelPredicate<'a, T> = Box<dyn Fn(&Progress<T>) -> bool + 'a>;
+
+/// Splits a large range into chunks and reduces them on every available core,
+/// for the puzzles where brute force really is the only option.
+pub(crate) struct Executor<'a, T> {
+    chunk_size: u64,
+    threads: NonZeroUsize,
+    report_every: Duration,
+    deadline: Option<Instant>,
+    on_progress: Option<ProgressCallback<'a, T>>,
+    cancel_when: Option<CancelPredicate<'a, T>>,
+}
+
+impl<'a, T> Executor<'a, T>
+where
+    T: Copy + Ord + Send + CheckedAdd,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            chunk_size: 1 << 16,
+            threads: available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            report_every: Duration::from_secs(1),
+            deadline: None,
+            on_progress: None,
+            cancel_when: None,
+        }
+    }
+
+    pub(crate) fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub(crate) fn threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// How often progress is reported and the cancellation checks run
+    pub(crate) fn report_every(mut self, interval: Duration) -> Self {
+        self.report_every = interval;
+        self
+    }
+
+    pub(crate) fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub(crate) fn on_progress(mut self, callback: impl FnMut(&Progress<T>) + 'a) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    pub(crate) fn cancel_when(mut self, predicate: impl Fn(&Progress<T>) -> bool + 'a) -> Self {
+        self.cancel_when = Some(Box::new(predicate));
+        self
+    }
+
+    /// Applies `map` to every number in `range` and reduces the results.
+    /// Chunks are handed out in ascending order.
+    pub(crate) fn run(
+        mut self,
+        range: Range<u64>,
+        reduction: Reduction,
+        map: impl Fn(u64) -> T + Sync,
+    ) -> Outcome<T> {
+        let total = range.end.saturating_sub(range.start);
+        let chunk_count = total.div_ceil(self.chunk_size);
+        let next_chunk = AtomicU64::new(0);
+        let processed = AtomicU64::new(0);
+        let finished_workers = AtomicUsize::new(0);
+        let cancelled = AtomicBool::new(false);
+        let overflowed = AtomicBool::new(false);
+        let result: Mutex<Option<T>> = Mutex::new(None);
+        let started = Instant::now();
+
+        let snapshot = || Progress {
+            processed: processed.load(Ordering::SeqCst),
+            total,
+            elapsed: started.elapsed(),
+            current: *result.lock().unwrap(),
+        };
+
+        // folds a chunk into the shared result, `None` when a sum overflows
+        let reduce_chunk = |mut numbers: Range<u64>| {
+            let local =
+                numbers.try_fold(None, |acc, n| reduction.combine(acc, map(n)).map(Some))?;
+            if let Some(local) = local {
+                let mut shared = result.lock().unwrap();
+                *shared = Some(reduction.combine(*shared, local)?);
+            }
+            Some(())
+        };
+
+        scope(|s| {
+            let workers = (0..self.threads.get())
+                .map(|_| {
+                    s.spawn(|| {
+                        // counts the worker as finished even when `map` panics,
+                        // and stops the others so the panic surfaces quickly
+                        let _finished = Finished {
+                            workers: &finished_workers,
+                            cancelled: &cancelled,
+                        };
+                        while !cancelled.load(Ordering::SeqCst) {
+                            let chunk = next_chunk.fetch_add(1, Ordering::SeqCst);
+                            if chunk >= chunk_count {
+                                break;
+                            }
+                            let start = range.start + chunk * self.chunk_size;
+                            let end = start.saturating_add(self.chunk_size).min(range.end);
+
+                            if reduce_chunk(start..end).is_none() {
+                                overflowed.store(true, Ordering::SeqCst);
+                                cancelled.store(true, Ordering::SeqCst);
+                                break;
+                            }
+                            processed.fetch_add(end - start, Ordering::SeqCst);
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // the calling thread watches over the workers
+            let poll = self.report_every.min(Duration::from_millis(10));
+            let mut last_report = Instant::now();
+            while finished_workers.load(Ordering::SeqCst) < self.threads.get() {
+                sleep(poll);
+                let progress = snapshot();
+                if last_report.elapsed() >= self.report_every {
+                    if let Some(callback) = self.on_progress.as_mut() {
+                        callback(&progress);
+                    }
+                    last_report = Instant::now();
+                }
+
+                let past_deadline = self.deadline.is_some_and(|d| Instant::now() >= d);
+                let predicate = self.cancel_when.as_ref().is_some_and(|p| p(&progress));
+                if past_deadline || predicate {
+                    cancelled.store(true, Ordering::SeqCst);
+                }
+            }
+
+            for worker in workers {
+                if let Err(panic) = worker.join() {
+                    resume_unwind(panic);
+                }
+            }
+        });
+
+        let progress = snapshot();
+        if let Some(callback) = self.on_progress.as_mut() {
+            callback(&progress);
+        }
+        if overflowed.load(Ordering::SeqCst) {
+            Outcome::Overflowed {
+                processed: progress.processed,
+            }
+        } else if cancelled.load(Ordering::SeqCst) && progress.processed < total {
+            Outcome::Cancelled {
+                partial: progress.current,
+                processed: progress.processed,
+            }
+        } else {
+            Outcome::Complete(progress.current)
+        }
+    }
+}
+
+/// Marks a worker as done when it goes out of scope, however it exits
+struct Finished<'a> {
+    workers: &'a AtomicUsize,
+    cancelled: &'a AtomicBool,
+}
+
+impl Drop for Finished<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.cancelled.store(true, Ordering::SeqCst);
+        }
+        self.workers.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn reductions() {
+    let executor = || {
+        Executor::new()
+            .chunk_size(1000)
+            .threads(NonZeroUsize::new(4).unwrap())
+    };
+
+    let sum = executor().run(0..100_000, Reduction::Sum, |n| n);
+    assert_eq!(sum, Outcome::Complete(Some(99_999 * 100_000 / 2)));
+
+    let max = executor().run(0..100_000, Reduction::Max, |n| (n * 7919) % 100_003);
+    assert_eq!(max, Outcome::Complete(Some(100_002)));
+
+    let min = executor().run(10..10, Reduction::Min, |n| n);
+    assert_eq!(min, Outcome::Complete(None));
+
+    // within a chunk, and across chunks
+    let sum = executor().run(0..10, Reduction::Sum, |_| u64::MAX / 4);
+    assert!(matches!(sum, Outcome::Overflowed { .. }), "{sum:?}");
+    let sum = executor().run(0..100_000, Reduction::Sum, |_| u64::MAX / 50_000);
+    assert!(matches!(sum, Outcome::Overflowed { processed } if processed < 100_000));
+    let sum = executor().run(0..4, Reduction::Sum, |_| u64::MAX / 4);
+    assert_eq!(sum, Outcome::Complete(Some(u64::MAX / 4 * 4)));
+}
+
+#[test]
+fn progress_and_cancellation() {
+    let mut reports = vec![];
+    let outcome = Executor::new()
+        .chunk_size(100)
+        .threads(NonZeroUsize::new(2).unwrap())
+        .report_every(Duration::from_millis(1))
+        .on_progress(|p: &Progress<u64>| reports.push(p.processed))
+        .cancel_when(|p| p.processed >= 1_000)
+        .run(0..u64::MAX, Reduction::Min, |n| {
+            sleep(Duration::from_micros(10));
+            n
+        });
+
+    match outcome {
+        Outcome::Cancelled { partial, processed } => {
+            assert_eq!(partial, Some(0));
+            assert!((1_000..u64::MAX).contains(&processed));
+        }
+        outcome => panic!("should have been cancelled, got {outcome:?}"),
+    }
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+
+    let outcome = Executor::new()
+        .deadline(Instant::now() + Duration::from_millis(20))
+        .run(0..u64::MAX, Reduction::Sum, |_| 0u64);
+    assert!(matches!(outcome, Outcome::Cancelled { .. }));
+}
+
+#[test]
+fn panics_reach_the_caller() {
+    let run = || {
+        Executor::new()
+            .chunk_size(2)
+            .threads(NonZeroUsize::new(3).unwrap())
+            .run(0..1_000, Reduction::Sum, |n| {
+                assert_ne!(n, 3, "boom");
+                n
+            })
+    };
+    let panic = std::panic::catch_unwind(run).unwrap_err();
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .unwrap_or_default();
+    assert!(message.contains("boom"), "{message}");
+}
@@ -0,0 +1,202 @@
+use std::ops::Range;
+
+/// A set of values stored as sorted, disjoint ranges. Touching or overlapping
+/// ranges are always coalesced, so two sets holding the same values compare equal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sorts the ranges, drops empty ones and merges the ones that touch or overlap
+    fn normalize(mut ranges: Vec<Range<T>>) -> Self {
+        ranges.retain(|r| r.start < r.end);
+        ranges.sort_by_key(|r| r.start);
+
+        let mut output: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match output.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => output.push(range),
+            }
+        }
+        Self { ranges: output }
+    }
+
+    pub(crate) fn insert(&mut self, range: Range<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalize(ranges);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub(crate) fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(index).is_some_and(|r| r.start <= value)
+    }
+
+    /// The coalesced pieces, in order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.iter().chain(other.iter()).collect())
+    }
+
+    pub(crate) fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut output = vec![];
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start < end {
+                output.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges: output }
+    }
+
+    /// Everything in `self` that isn't in `other`
+    pub(crate) fn difference(&self, other: &Self) -> Self {
+        let mut output = vec![];
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let cut = &other.ranges[k];
+                if cut.start > start {
+                    output.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < range.end {
+                output.push(start..range.end);
+            }
+        }
+        Self { ranges: output }
+    }
+
+    /// The pieces of the set, additionally cut wherever one of the sorted
+    /// `boundaries` falls strictly inside a piece
+    pub(crate) fn split_at(&self, boundaries: &[T]) -> Vec<Range<T>> {
+        let mut output = vec![];
+        for range in &self.ranges {
+            let first = boundaries.partition_point(|b| *b <= range.start);
+            let mut start = range.start;
+            for boundary in boundaries[first..].iter().take_while(|b| **b < range.end) {
+                // repeated boundaries would cut out empty pieces
+                if *boundary <= start {
+                    continue;
+                }
+                output.push(start..*boundary);
+                start = *boundary;
+            }
+            output.push(start..range.end);
+        }
+        output
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::normalize(vec![range])
+    }
+}
+
+#[cfg(test)]
+fn to_bits(set: &IntervalSet<u32>) -> u64 {
+    set.iter()
+        .flat_map(|r| r.into_iter())
+        .fold(0, |bits, v| bits | (1 << v))
+}
+
+#[test]
+fn coalesces_pieces() {
+    let set: IntervalSet<u32> = [5..8, 0..2, 2..3, 7..10, 12..12].into_iter().collect();
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..3, 5..10]);
+    assert!(set.contains(2) && !set.contains(3) && set.contains(9));
+    assert_eq!(set.split_at(&[1, 3, 6, 20]), vec![0..1, 1..3, 5..6, 6..10]);
+    assert_eq!(set.split_at(&[1, 1, 6, 6]), vec![0..1, 1..3, 5..6, 6..10]);
+    assert_eq!(
+        IntervalSet::from(0..10).split_at(&[5, 5]),
+        vec![0..5, 5..10]
+    );
+}
+
+/// Checks every operation against a bitset of the same 64 values
+/// over a few thousand randomly generated sets
+#[test]
+fn matches_bitset_model() {
+    // a small linear congruential generator keeps the cases reproducible
+    let mut state = 0x853c_49e6_748f_ea9bu64;
+    let mut next = move |bound: u32| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as u32 % bound
+    };
+    let random_set = |next: &mut dyn FnMut(u32) -> u32| {
+        let count = next(6);
+        (0..count)
+            .map(|_| {
+                let start = next(64);
+                start..(start + next(16)).min(64)
+            })
+            .collect::<IntervalSet<u32>>()
+    };
+
+    for _ in 0..5000 {
+        let (a, b) = (random_set(&mut next), random_set(&mut next));
+        let (bits_a, bits_b) = (to_bits(&a), to_bits(&b));
+
+        assert_eq!(to_bits(&a.union(&b)), bits_a | bits_b);
+        assert_eq!(to_bits(&a.intersection(&b)), bits_a & bits_b);
+        assert_eq!(to_bits(&a.difference(&b)), bits_a & !bits_b);
+        for value in 0..64 {
+            assert_eq!(a.contains(value), bits_a >> value & 1 == 1);
+        }
+
+        // pieces never touch, so normalizing again changes nothing
+        assert_eq!(a.iter().collect::<IntervalSet<_>>(), a);
+        assert!(a.iter().zip(a.iter().skip(1)).all(|(x, y)| x.end < y.start));
+
+        let mut boundaries = (0..next(5)).map(|_| next(64)).collect::<Vec<_>>();
+        boundaries.sort();
+        let pieces = a.split_at(&boundaries);
+        assert_eq!(pieces.iter().cloned().collect::<IntervalSet<_>>(), a);
+        assert!(pieces.iter().all(|p| !p.is_empty()), "{pieces:?}");
+        assert!(pieces
+            .iter()
+            .all(|p| !boundaries.iter().any(|b| p.start < *b && *b < p.end)));
+    }
+}
@@ -1,6 +1,12 @@
+mod cycle;
 mod day_1;
 mod day_2;
 mod day_3;
 mod day_4;
 mod day_5;
 mod day_6;
+mod diagnostic;
+mod executor;
+mod interval;
+mod parse;
+mod stream;
@@ -0,0 +1,280 @@
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::diagnostic::Diagnostic;
+
+/// Puzzle input together with the file it came from, when there is one
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct Source<'a> {
+    pub file: Option<&'a str>,
+    pub text: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub(crate) fn named(file: &'a str, text: &'a str) -> Self {
+        Self {
+            file: Some(file),
+            text,
+        }
+    }
+
+    /// Every line of the input, numbered from 1
+    pub(crate) fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        let file = self.file;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(index, text)| Line::new(index + 1, text).in_file(file))
+    }
+
+    /// The runs of lines between blank lines. Line numbers still count the
+    /// blank lines, so they point into the original input.
+    pub(crate) fn sections(&self) -> Vec<Vec<Line<'a>>> {
+        let mut output = vec![];
+        let mut section = vec![];
+        for line in self.lines() {
+            if line.is_blank() {
+                if !section.is_empty() {
+                    output.push(std::mem::take(&mut section));
+                }
+            } else {
+                section.push(line);
+            }
+        }
+        if !section.is_empty() {
+            output.push(section);
+        }
+        output
+    }
+
+    /// An empty line just past the end, for errors about missing input
+    pub(crate) fn end(&self) -> Line<'a> {
+        Line::new(self.text.lines().count() + 1, "").in_file(self.file)
+    }
+}
+
+impl<'a> From<&'a str> for Source<'a> {
+    fn from(text: &'a str) -> Self {
+        Self { file: None, text }
+    }
+}
+
+/// A piece of puzzle input that remembers where it came from, so errors
+/// raised while taking it apart point at the offending text. Every
+/// combinator hands back more `Line`s, trimmed of surrounding whitespace.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct Line<'a> {
+    pub file: Option<&'a str>,
+    /// 1-based line number within the input
+    pub number: usize,
+    /// The whole input line, `text` is always a slice of it
+    full: &'a str,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub(crate) fn new(number: usize, text: &'a str) -> Self {
+        Self {
+            file: None,
+            number,
+            full: text,
+            text: text.trim(),
+        }
+    }
+
+    pub(crate) fn in_file(self, file: Option<&'a str>) -> Self {
+        Self { file, ..self }
+    }
+
+    /// Another piece of the same line
+    fn part(&self, text: &'a str) -> Self {
+        Self {
+            text: text.trim(),
+            ..*self
+        }
+    }
+
+    /// Where `text` sits within the whole line
+    fn span(&self) -> Range<usize> {
+        let start = (self.text.as_ptr() as usize)
+            .checked_sub(self.full.as_ptr() as usize)
+            .filter(|start| start + self.text.len() <= self.full.len())
+            .unwrap_or(0);
+        start..start + self.text.len()
+    }
+
+    pub(crate) fn diagnostic(&self, message: impl Display) -> Diagnostic {
+        Diagnostic {
+            message: message.to_string(),
+            file: self.file.map(str::to_string),
+            line: self.number,
+            span: self.span(),
+            source: self.full.to_string(),
+        }
+    }
+
+    /// A [`Diagnostic`] underlining this piece of the line
+    pub(crate) fn error(&self, message: impl Display) -> eyre::Report {
+        eyre::Report::new(self.diagnostic(message))
+    }
+
+    pub(crate) fn is_blank(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Splits a `Card 12: ...` line into the number after `label` and
+    /// whatever follows the colon
+    pub(crate) fn labeled(&self, label: &str) -> eyre::Result<(usize, Line<'a>)> {
+        let (header, rest) = self
+            .text
+            .split_once(':')
+            .ok_or_else(|| self.error(format!("Expected `{label} N:`")))?;
+        let header = self.part(header);
+        let id = header
+            .text
+            .strip_prefix(label)
+            .ok_or_else(|| header.error(format!("Expected a {label} header")))?;
+        Ok((self.part(id).number()?, self.part(rest)))
+    }
+
+    /// The text after a `label:` with no number, like `Time: ...`
+    pub(crate) fn field(&self, label: &str) -> eyre::Result<Line<'a>> {
+        self.text
+            .strip_prefix(label)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(|rest| self.part(rest))
+            .ok_or_else(|| self.error(format!("Expected `{label}:`")))
+    }
+
+    /// The two category names of an `a-to-b map:` header
+    pub(crate) fn map_header(&self) -> eyre::Result<(&'a str, &'a str)> {
+        self.text
+            .strip_suffix("map:")
+            .and_then(|names| names.trim().split_once("-to-"))
+            .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+            .ok_or_else(|| self.error("Expected an `a-to-b map:` header"))
+    }
+
+    /// The pieces between `separator`s, like the `|` groups of a card
+    pub(crate) fn groups(&self, separator: char) -> impl Iterator<Item = Line<'a>> {
+        let this = *self;
+        self.text
+            .split(separator)
+            .map(move |group| this.part(group))
+    }
+
+    /// The two sides of a line holding exactly one `separator`
+    pub(crate) fn split_pair(&self, separator: char) -> eyre::Result<(Line<'a>, Line<'a>)> {
+        let Some((left, right)) = self.text.split_once(separator) else {
+            return Err(self.error(format!("Expected a `{separator}`")));
+        };
+        match right.find(separator) {
+            None => Ok((self.part(left), self.part(right))),
+            // underline the extra separator
+            Some(at) => Err(self
+                .part(&right[at..at + separator.len_utf8()])
+                .error(format!("Expected a single `{separator}`"))),
+        }
+    }
+
+    pub(crate) fn words(&self) -> impl Iterator<Item = Line<'a>> {
+        let this = *self;
+        self.text
+            .split_whitespace()
+            .map(move |word| this.part(word))
+    }
+
+    pub(crate) fn number<T>(&self) -> eyre::Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.text
+            .parse()
+            .map_err(|e| self.error(format!("Not a valid number {}: {e}", self.text)))
+    }
+
+    /// A whitespace separated list of numbers
+    pub(crate) fn numbers<T>(&self) -> eyre::Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.words().map(|word| word.number()).collect()
+    }
+}
+
+#[test]
+fn headers_and_groups() {
+    let line = Line::new(3, "  Card  12: 41 48 | 83 86  6 ");
+    let (id, rest) = line.labeled("Card").unwrap();
+    assert_eq!((id, rest.number), (12, 3));
+
+    let (numbers, winners) = rest.split_pair('|').unwrap();
+    assert_eq!(numbers.numbers::<u8>().unwrap(), vec![41, 48]);
+    assert_eq!(winners.numbers::<u8>().unwrap(), vec![83, 86, 6]);
+    assert_eq!(rest.groups('|').count(), 2);
+
+    assert_eq!(
+        Line::new(1, "Time:  7 15").field("Time").unwrap().text,
+        "7 15"
+    );
+    assert_eq!(
+        Line::new(1, "seed-to-soil map:").map_header().unwrap(),
+        ("seed", "soil")
+    );
+}
+
+#[test]
+fn errors_point_at_the_text() {
+    let line = Line::new(7, "Card 1: 1 2 | 3 | 4").in_file(Some("cards.txt"));
+    let diagnostic = |error: eyre::Report| error.downcast::<Diagnostic>().unwrap();
+
+    let error = diagnostic(line.split_pair('|').unwrap_err());
+    assert_eq!(
+        (error.line, error.column(), error.span.clone()),
+        (7, 17, 16..17)
+    );
+    assert_eq!(
+        error.to_string(),
+        "cards.txt:7:17: Expected a single `|`\n  |\n7 | Card 1: 1 2 | 3 | 4\n  |                 ^"
+    );
+
+    let error = diagnostic(line.labeled("Game").unwrap_err());
+    assert_eq!(
+        (error.message.as_str(), error.span),
+        ("Expected a Game header", 0..6)
+    );
+
+    let error = diagnostic(Line::new(2, "  1 2 three").numbers::<u8>().unwrap_err());
+    assert_eq!(
+        (error.message.as_str(), error.file, error.span),
+        (
+            "Not a valid number three: invalid digit found in string",
+            None,
+            6..11
+        )
+    );
+
+    let errors = [
+        Line::new(7, "Card x: 1").labeled("Card").unwrap_err(),
+        Line::new(7, "seed-soil map:").map_header().unwrap_err(),
+        Line::new(7, "Distance: 9").field("Time").unwrap_err(),
+    ];
+    for error in errors {
+        assert_eq!(diagnostic(error).line, 7);
+    }
+}
+
+#[test]
+fn blank_lines_split_sections() {
+    let input = "\nseeds: 1 2\n\n\na-to-b map:\n1 2 3\n  \nb-to-c map:\n";
+    let sections = Source::named("almanac.txt", input).sections();
+    assert_eq!(sections[1][1].file, Some("almanac.txt"));
+    let numbers = sections
+        .iter()
+        .map(|s| s.iter().map(|l| l.number).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, vec![vec![2], vec![5, 6], vec![8]]);
+}
@@ -0,0 +1,103 @@
+use std::io::BufRead;
+#[cfg(test)]
+use std::io::Read;
+
+use eyre::Context;
+
+/// Calls `f` with every line of `reader` and its 1-based line number. One
+/// buffer is reused for every line, so memory stays flat however long the
+/// input is. Line endings are stripped before `f` sees the line.
+pub(crate) fn try_for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    let mut buffer = String::new();
+    let mut number = 0;
+    loop {
+        number += 1;
+        buffer.clear();
+        let read = reader
+            .read_line(&mut buffer)
+            .with_context(|| format!("Could not read line {number}"))?;
+        if read == 0 {
+            return Ok(());
+        }
+        f(number, buffer.trim_end_matches(['\n', '\r']))?;
+    }
+}
+
+/// Synthetic input for stress tests: `line` repeated `times` times, produced
+/// on demand instead of being held in memory
+#[cfg(test)]
+pub(crate) struct Repeated {
+    line: &'static [u8],
+    remaining: usize,
+    offset: usize,
+}
+
+#[cfg(test)]
+impl Repeated {
+    pub(crate) fn new(line: &'static str, times: usize) -> Self {
+        Self {
+            line: line.as_bytes(),
+            remaining: times,
+            offset: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Read for Repeated {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+impl BufRead for Repeated {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.remaining == 0 {
+            return Ok(&[]);
+        }
+        Ok(&self.line[self.offset..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.offset += amount;
+        if self.offset >= self.line.len() {
+            self.offset = 0;
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+    }
+}
+
+#[test]
+fn numbers_lines_and_strips_endings() {
+    let mut seen = vec![];
+    try_for_each_line("a\r\n\nb".as_bytes(), |number, line| {
+        seen.push((number, line.to_string()));
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(seen, vec![(1, "a".into()), (2, "".into()), (3, "b".into())]);
+
+    let error = try_for_each_line(Repeated::new("x\n", 5), |number, _| {
+        eyre::ensure!(number < 4, "stop at {number}");
+        Ok(())
+    })
+    .unwrap_err();
+    assert_eq!(error.to_string(), "stop at 4");
+
+    let mut count = 0;
+    try_for_each_line(Repeated::new("line\n", 1000), |_, line| {
+        assert_eq!(line, "line");
+        count += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(count, 1000);
+}